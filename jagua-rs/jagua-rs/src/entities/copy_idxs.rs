@@ -0,0 +1,126 @@
+use crate::entities::PlacedItem;
+use std::collections::BTreeSet;
+
+/// Keeps track of the copy indices in use for every item, see [`PlacedItem::copy_idx`].
+/// The lowest free index of an item is found in logarithmic time, without scanning the placed items.
+#[derive(Clone, Debug, Default)]
+pub struct CopyIdxTracker {
+    /// Copy indices of every item, indexed by item id
+    items: Vec<ItemCopyIdxs>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ItemCopyIdxs {
+    /// Indices below `n_issued` which are not in use
+    free: BTreeSet<usize>,
+    /// All indices from `n_issued` onwards are not in use
+    n_issued: usize,
+}
+
+impl CopyIdxTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker with the copy indices of the placed items in use
+    pub fn from_placed_items<'a>(placed_items: impl IntoIterator<Item = &'a PlacedItem>) -> Self {
+        let mut tracker = Self::new();
+        for pi in placed_items {
+            tracker.take(pi.item_id, pi.copy_idx);
+        }
+        tracker
+    }
+
+    /// Returns the lowest copy index of an item which is not in use
+    pub fn lowest_free(&self, item_id: usize) -> usize {
+        match self.items.get(item_id) {
+            Some(idxs) => idxs.free.first().copied().unwrap_or(idxs.n_issued),
+            None => 0,
+        }
+    }
+
+    pub fn is_free(&self, item_id: usize, copy_idx: usize) -> bool {
+        match self.items.get(item_id) {
+            Some(idxs) => copy_idx >= idxs.n_issued || idxs.free.contains(&copy_idx),
+            None => true,
+        }
+    }
+
+    /// Marks a copy index of an item as in use
+    pub fn take(&mut self, item_id: usize, copy_idx: usize) {
+        debug_assert!(
+            self.is_free(item_id, copy_idx),
+            "copy {copy_idx} of item {item_id} is already in use"
+        );
+        if item_id >= self.items.len() {
+            self.items.resize_with(item_id + 1, Default::default);
+        }
+        let idxs = &mut self.items[item_id];
+        if copy_idx >= idxs.n_issued {
+            //all skipped indices remain free
+            idxs.free.extend(idxs.n_issued..copy_idx);
+            idxs.n_issued = copy_idx + 1;
+        } else {
+            idxs.free.remove(&copy_idx);
+        }
+    }
+
+    /// Marks a copy index of an item as no longer in use
+    pub fn release(&mut self, item_id: usize, copy_idx: usize) {
+        debug_assert!(
+            !self.is_free(item_id, copy_idx),
+            "copy {copy_idx} of item {item_id} is not in use"
+        );
+        let idxs = &mut self.items[item_id];
+        idxs.free.insert(copy_idx);
+        //keep the set of free indices small by lowering the issued ones
+        while idxs.n_issued > 0 && idxs.free.remove(&(idxs.n_issued - 1)) {
+            idxs.n_issued -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_free_copy_idx() {
+        let mut tracker = CopyIdxTracker::new();
+        assert_eq!(tracker.lowest_free(3), 0);
+
+        for copy_idx in 0..4 {
+            tracker.take(0, copy_idx);
+        }
+        assert_eq!(tracker.lowest_free(0), 4);
+        //other items are unaffected
+        assert_eq!(tracker.lowest_free(1), 0);
+
+        tracker.release(0, 2);
+        tracker.release(0, 1);
+        assert_eq!(tracker.lowest_free(0), 1);
+        assert!(tracker.is_free(0, 2));
+        tracker.take(0, 1);
+        assert_eq!(tracker.lowest_free(0), 2);
+
+        //releasing the highest index lowers the issued ones past the free ones
+        tracker.release(0, 3);
+        assert_eq!(tracker.lowest_free(0), 2);
+        assert_eq!(tracker.items[0].n_issued, 2);
+        assert!(tracker.items[0].free.is_empty());
+    }
+
+    #[test]
+    fn take_beyond_issued_copy_idxs() {
+        let mut tracker = CopyIdxTracker::new();
+        tracker.take(2, 3);
+        assert!(!tracker.is_free(2, 3));
+        assert!((0..3).all(|idx| tracker.is_free(2, idx)));
+        assert_eq!(tracker.lowest_free(2), 0);
+
+        for copy_idx in 0..3 {
+            tracker.take(2, copy_idx);
+        }
+        assert_eq!(tracker.lowest_free(2), 4);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::collision_detection::hazards::Hazard;
 use crate::collision_detection::{CDESnapshot, CDEngine};
 use crate::entities::Item;
-use crate::entities::{Container, Instance};
+use crate::entities::{Container, CopyIdxTracker, Instance};
 use crate::entities::{PItemKey, PlacedItem};
 use crate::geometry::DTransformation;
 use crate::util::assertions;
@@ -18,6 +18,8 @@ pub struct Layout {
     pub placed_items: SlotMap<PItemKey, PlacedItem>,
     /// The collision detection engine for this layout
     cde: CDEngine,
+    /// The copy indices of the placed items
+    copy_idxs: CopyIdxTracker,
 }
 
 impl Layout {
@@ -27,6 +29,7 @@ impl Layout {
             container,
             placed_items: SlotMap::with_key(),
             cde,
+            copy_idxs: CopyIdxTracker::new(),
         }
     }
 
@@ -62,17 +65,32 @@ impl Layout {
         assert_eq!(self.container.id, layout_snapshot.container.id);
         self.placed_items = layout_snapshot.placed_items.clone();
         self.cde.restore(&layout_snapshot.cde_snapshot);
+        self.copy_idxs = CopyIdxTracker::from_placed_items(self.placed_items.values());
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
         debug_assert!(assertions::layouts_match(self, layout_snapshot))
     }
 
     /// Places an item in the layout at a specific position by applying a transformation.
+    /// The item is placed as its lowest copy which is not in the layout yet, see [`Layout::next_copy_idx`].
     /// Returns the unique key for the placed item.
     pub fn place_item(&mut self, item: &Item, d_transformation: DTransformation) -> PItemKey {
+        let copy_idx = self.next_copy_idx(item.id);
+        self.place_item_copy(item, d_transformation, copy_idx)
+    }
+
+    /// Places a specific copy of an item in the layout at a specific position by applying a transformation.
+    /// The copy should not be in the layout yet. Returns the unique key for the placed item.
+    pub fn place_item_copy(
+        &mut self,
+        item: &Item,
+        d_transformation: DTransformation,
+        copy_idx: usize,
+    ) -> PItemKey {
+        self.copy_idxs.take(item.id, copy_idx);
         let pk = self
             .placed_items
-            .insert(PlacedItem::new(item, d_transformation));
+            .insert(PlacedItem::new(item, d_transformation, copy_idx));
         let pi = &self.placed_items[pk];
         let hazard = Hazard::new((pk, pi).into(), pi.shape.clone(), true);
 
@@ -92,12 +110,23 @@ impl Layout {
 
         // update the collision detection engine
         self.cde.deregister_hazard_by_entity((pk, &pi).into());
+        self.copy_idxs.release(pi.item_id, pi.copy_idx);
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
 
         pi
     }
 
+    /// Returns the lowest copy index of an item which is not in use by any of its placed copies.
+    pub fn next_copy_idx(&self, item_id: usize) -> usize {
+        self.copy_idxs.lowest_free(item_id)
+    }
+
+    /// Returns true if the copy of the item is placed in the layout
+    pub fn is_copy_placed(&self, item_id: usize, copy_idx: usize) -> bool {
+        !self.copy_idxs.is_free(item_id, copy_idx)
+    }
+
     /// True if no items are placed
     pub fn is_empty(&self) -> bool {
         self.placed_items.is_empty()
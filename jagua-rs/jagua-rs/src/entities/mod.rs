@@ -1,4 +1,5 @@
 mod container;
+mod copy_idxs;
 mod instance;
 mod item;
 mod layout;
@@ -25,6 +26,9 @@ pub use placed_item::PItemKey;
 #[doc(inline)]
 pub use container::Container;
 
+#[doc(inline)]
+pub use copy_idxs::CopyIdxTracker;
+
 #[doc(inline)]
 pub use container::InferiorQualityZone;
 
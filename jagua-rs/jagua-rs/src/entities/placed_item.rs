@@ -17,6 +17,9 @@ new_key_type! {
 pub struct PlacedItem {
     /// ID of the type of `Item` that was placed
     pub item_id: usize,
+    /// Index of this copy among all copies of the `Item`, it identifies the copy regardless of where it is (re)placed.
+    /// Together with `item_id`, it forms a stable identifier of the physical part.
+    pub copy_idx: usize,
     /// The transformation that was applied to the `Item` before it was placed
     pub d_transf: DTransformation,
     /// The shape of the `Item` after it has been transformed and placed in a `Layout`
@@ -24,12 +27,13 @@ pub struct PlacedItem {
 }
 
 impl PlacedItem {
-    pub fn new(item: &Item, d_transf: DTransformation) -> Self {
+    pub fn new(item: &Item, d_transf: DTransformation, copy_idx: usize) -> Self {
         let transf = d_transf.compose();
         let shape = item.shape_cd.transform_clone(&transf);
 
         PlacedItem {
             item_id: item.id,
+            copy_idx,
             d_transf,
             shape,
         }
@@ -77,3 +77,24 @@ pub fn normalize_rotation(r: f64) -> f64 {
         normalized
     }
 }
+
+/// Returns the rotation within one of the `intervals` (each `(start, end)` in radians) closest to `r`.
+/// Angles are compared modulo 2π, the returned rotation lies within the bounds of the interval as specified.
+pub fn closest_rotation_in_intervals(r: f64, intervals: &[(f64, f64)]) -> f64 {
+    let mut closest = (f64::INFINITY, r);
+    for &(start, end) in intervals {
+        //angle from the start of the interval to r, in [0, 2π)
+        let delta = normalize_rotation(r - start);
+        if delta <= end - start {
+            return start + delta;
+        }
+        let (d_start, d_end) = (2.0 * PI - delta, delta - (end - start));
+        if d_start < closest.0 {
+            closest = (d_start, start);
+        }
+        if d_end < closest.0 {
+            closest = (d_end, end);
+        }
+    }
+    closest.1
+}
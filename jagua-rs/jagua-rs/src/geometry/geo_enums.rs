@@ -26,4 +26,6 @@ pub enum RotationRange {
     Continuous,
     /// Discrete set of rotations allowed
     Discrete(Vec<f64>),
+    /// Set of allowed rotation intervals, each defined by its start and end (in radians, with start <= end)
+    Intervals(Vec<(f64, f64)>),
 }
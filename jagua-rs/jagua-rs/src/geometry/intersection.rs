@@ -0,0 +1,258 @@
+use itertools::Itertools;
+use ordered_float::OrderedFloat;
+
+use crate::geometry::geo_traits::{CollidesWith, DistanceTo};
+use crate::geometry::primitives::Edge;
+use crate::geometry::primitives::Point;
+use crate::geometry::primitives::SPolygon;
+
+/// Tolerance, relative to the largest diameter of the two polygons, below which two points are considered coincident.
+const EPS_DIAM_RATIO: f64 = 1e-10;
+
+/// Computes the exact area of the intersection between two [`SPolygon`]s.
+///
+/// Works for any pair of simple polygons (convex or concave) and is robust to shared (collinear) edges and collinear vertices.
+/// Polygons which only touch along their boundaries have an intersection area of zero.
+pub fn intersection_area(s1: &SPolygon, s2: &SPolygon) -> f64 {
+    if !s1.bbox.collides_with(&s2.bbox) {
+        return 0.0;
+    }
+    let eps = f64::max(s1.diameter, s2.diameter) * EPS_DIAM_RATIO;
+
+    //Green's theorem over the boundary of the intersection (shoelace formula).
+    //Coordinates are taken relative to a local origin to limit cancellation errors.
+    let Point(ox, oy) = s1.bbox.centroid();
+    let double_area = intersection_boundary(s1, s2, eps)
+        .iter()
+        .map(|e| {
+            let (x1, y1) = (e.start.0 - ox, e.start.1 - oy);
+            let (x2, y2) = (e.end.0 - ox, e.end.1 - oy);
+            x1 * y2 - x2 * y1
+        })
+        .sum::<f64>();
+
+    f64::max(0.5 * double_area, 0.0)
+}
+
+/// Computes the intersection between two [`SPolygon`]s as a set of disjoint simple polygons.
+///
+/// Returns an empty vector if the polygons do not overlap or only touch along their boundaries.
+/// The sum of the areas of the returned polygons equals [`intersection_area`] (up to floating point precision).
+pub fn intersection(s1: &SPolygon, s2: &SPolygon) -> Vec<SPolygon> {
+    if !s1.bbox.collides_with(&s2.bbox) {
+        return vec![];
+    }
+    let eps = f64::max(s1.diameter, s2.diameter) * EPS_DIAM_RATIO;
+
+    let boundary = intersection_boundary(s1, s2, eps);
+
+    assemble_loops(boundary, eps)
+        .into_iter()
+        .map(|points| remove_collinear_points(points, eps))
+        .filter(|points| points.len() >= 3)
+        .filter(|points| SPolygon::calculate_area(points) > eps * eps)
+        .filter_map(|points| SPolygon::new(points).ok())
+        .collect()
+}
+
+/// Collects the directed segments which form the boundary of the intersection of `s1` and `s2`:
+/// the parts of the boundary of `s1` inside `s2` and vice versa.
+/// Segments shared by both boundaries are included once, and only if the interiors of both polygons lie on the same side.
+fn intersection_boundary(s1: &SPolygon, s2: &SPolygon, eps: f64) -> Vec<Edge> {
+    let mut segments = vec![];
+    //shared segments are only collected from the boundary of the first polygon to avoid counting them twice
+    for (shape, other, collect_shared) in [(s1, s2, true), (s2, s1, false)] {
+        for edge in shape.edge_iter() {
+            let params = split_params(&edge, other, eps);
+            for (&t_start, &t_end) in params.iter().tuple_windows() {
+                let start = point_on_edge(&edge, t_start);
+                let end = point_on_edge(&edge, t_end);
+                if start.sq_distance_to(&end) <= eps * eps {
+                    continue;
+                }
+                let mid = Point((start.0 + end.0) / 2.0, (start.1 + end.1) / 2.0);
+                let include = match locate(mid, other, eps) {
+                    Location::Interior => true,
+                    Location::Exterior => false,
+                    Location::Boundary(other_edge) => {
+                        //both polygons are counterclockwise, same direction means the interiors are on the same side
+                        collect_shared && same_direction(&edge, &other_edge)
+                    }
+                };
+                if include {
+                    segments.push(Edge { start, end });
+                }
+            }
+        }
+    }
+    segments
+}
+
+/// Returns the sorted parameters (in [0, 1]) at which `edge` should be split to separate its parts inside and outside of `other`.
+fn split_params(edge: &Edge, other: &SPolygon, eps: f64) -> Vec<f64> {
+    let (dx, dy) = (edge.end.0 - edge.start.0, edge.end.1 - edge.start.1);
+    let length = edge.length();
+    let mut params = vec![0.0, 1.0];
+
+    //vertices of the other polygon which lie on the edge (touching vertices and collinear overlaps)
+    for v in other.vertices.iter() {
+        if edge.sq_distance_to(v) <= eps * eps {
+            params.push(((v.0 - edge.start.0) * dx + (v.1 - edge.start.1) * dy) / (length * length));
+        }
+    }
+
+    //proper crossings with the edges of the other polygon
+    params.extend(other.edge_iter().filter_map(|o| crossing_param(edge, &o)));
+
+    params.iter_mut().for_each(|t| *t = t.clamp(0.0, 1.0));
+    params.sort_by_key(|t| OrderedFloat(*t));
+    params.dedup_by(|t, prev_t| (*t - *prev_t) * length <= eps);
+    //make sure the edge ends exactly at its end point
+    *params.last_mut().unwrap() = 1.0;
+
+    params
+}
+
+/// Returns the parameter along `e1` at which it crosses `e2`, if the edges are not parallel and intersect.
+fn crossing_param(e1: &Edge, e2: &Edge) -> Option<f64> {
+    let (d1x, d1y) = (e1.end.0 - e1.start.0, e1.end.1 - e1.start.1);
+    let (d2x, d2y) = (e2.end.0 - e2.start.0, e2.end.1 - e2.start.1);
+    let denom = d1x * d2y - d1y * d2x;
+    if denom.abs() <= f64::EPSILON * e1.length() * e2.length() {
+        //parallel edges, collinear overlaps are handled by the vertex projections
+        return None;
+    }
+    let (wx, wy) = (e2.start.0 - e1.start.0, e2.start.1 - e1.start.1);
+    let t = (wx * d2y - wy * d2x) / denom;
+    let u = (wx * d1y - wy * d1x) / denom;
+
+    match (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        true => Some(t),
+        false => None,
+    }
+}
+
+fn point_on_edge(edge: &Edge, t: f64) -> Point {
+    match t {
+        0.0 => edge.start,
+        1.0 => edge.end,
+        _ => Point(
+            edge.start.0 + t * (edge.end.0 - edge.start.0),
+            edge.start.1 + t * (edge.end.1 - edge.start.1),
+        ),
+    }
+}
+
+fn same_direction(e1: &Edge, e2: &Edge) -> bool {
+    let dot = (e1.end.0 - e1.start.0) * (e2.end.0 - e2.start.0)
+        + (e1.end.1 - e1.start.1) * (e2.end.1 - e2.start.1);
+    dot > 0.0
+}
+
+enum Location {
+    Interior,
+    Exterior,
+    /// On the boundary, lying on the given edge
+    Boundary(Edge),
+}
+
+fn locate(p: Point, shape: &SPolygon, eps: f64) -> Location {
+    match shape.edge_iter().find(|e| e.sq_distance_to(&p) <= eps * eps) {
+        Some(edge) => Location::Boundary(edge),
+        None => match shape.collides_with(&p) {
+            true => Location::Interior,
+            false => Location::Exterior,
+        },
+    }
+}
+
+/// Chains directed segments into closed loops.
+/// When multiple segments leave the same point (polygons touching in a single point), the leftmost turn is taken,
+/// which keeps every loop simple.
+fn assemble_loops(mut segments: Vec<Edge>, eps: f64) -> Vec<Vec<Point>> {
+    let mut loops = vec![];
+    while let Some(first) = segments.pop() {
+        let mut points = vec![first.start];
+        let mut current = first;
+        loop {
+            if current.end.sq_distance_to(&points[0]) <= eps * eps {
+                loops.push(points);
+                break;
+            }
+            let next = segments
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.start.sq_distance_to(&current.end) <= eps * eps)
+                .max_by_key(|(_, s)| OrderedFloat(turn_angle(&current, s)))
+                .map(|(i, _)| i);
+            match next {
+                Some(i) => {
+                    points.push(current.end);
+                    current = segments.swap_remove(i);
+                }
+                None => break, //open chain (numerical artifact), discard it
+            }
+        }
+    }
+    loops
+}
+
+/// Signed angle between the directions of two consecutive edges (positive is a left turn).
+fn turn_angle(e1: &Edge, e2: &Edge) -> f64 {
+    let (d1x, d1y) = (e1.end.0 - e1.start.0, e1.end.1 - e1.start.1);
+    let (d2x, d2y) = (e2.end.0 - e2.start.0, e2.end.1 - e2.start.1);
+    f64::atan2(d1x * d2y - d1y * d2x, d1x * d2x + d1y * d2y)
+}
+
+/// Removes points which (almost) lie on the line through their neighbors.
+fn remove_collinear_points(mut points: Vec<Point>, eps: f64) -> Vec<Point> {
+    let mut i = 0;
+    while points.len() >= 3 && i < points.len() {
+        let n = points.len();
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        let redundant = match Edge::try_new(prev, next) {
+            Ok(e) => e.sq_distance_to(&points[i]) <= eps * eps,
+            Err(_) => true,
+        };
+        match redundant {
+            true => {
+                points.remove(i);
+                //the previous point might have become collinear
+                i = i.saturating_sub(1);
+            }
+            false => i += 1,
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::assert_approx_eq;
+    use test_case::test_case;
+
+    fn polygon(points: &[(f64, f64)]) -> SPolygon {
+        SPolygon::new(points.iter().map(|&p| Point::from(p)).collect()).unwrap()
+    }
+
+    const SQUARE: [(f64, f64); 4] = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+
+    #[test_case(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)], 1.0; "partial overlap")]
+    #[test_case(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)], 4.0; "identical")]
+    #[test_case(&[(2.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.0)], 0.0; "shared edge")]
+    #[test_case(&[(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)], 0.0; "shared vertex")]
+    #[test_case(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)], 2.0; "shared edge with collinear vertex")]
+    #[test_case(&[(0.5, 0.5), (1.5, 0.5), (1.5, 1.5), (0.5, 1.5)], 1.0; "contained")]
+    #[test_case(&[(-1.0, -1.0), (3.0, -1.0), (3.0, 0.5), (0.5, 0.5), (0.5, 1.5), (3.0, 1.5), (3.0, 3.0), (-1.0, 3.0)], 2.5; "concave")]
+    fn exact_intersection_area(other: &[(f64, f64)], expected: f64) {
+        let (s1, s2) = (polygon(&SQUARE), polygon(other));
+
+        assert_approx_eq!(f64, intersection_area(&s1, &s2), expected, epsilon = 1e-9);
+        assert_approx_eq!(f64, intersection_area(&s2, &s1), expected, epsilon = 1e-9);
+
+        let inters_area = intersection(&s1, &s2).iter().map(|p| p.area).sum::<f64>();
+        assert_approx_eq!(f64, inters_area, expected, epsilon = 1e-9);
+    }
+}
@@ -12,6 +12,9 @@ pub mod geo_enums;
 /// Set of traits representing various geometric properties & operations
 pub mod geo_traits;
 
+/// Set of functions to compute the exact intersection (area) of geometric shapes
+pub mod intersection;
+
 /// Set of geometric primitives - atomic building blocks for the geometry module
 pub mod primitives;
 mod transformation;
@@ -31,4 +34,4 @@ pub use transformation::Transformation;
 pub use original_shape::OriginalShape;
 
 #[doc(inline)]
-pub use d_transformation::normalize_rotation;
+pub use d_transformation::{closest_rotation_in_intervals, normalize_rotation};
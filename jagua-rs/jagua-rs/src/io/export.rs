@@ -1,12 +1,15 @@
 use crate::entities::{Instance, LayoutSnapshot};
 use crate::geometry::{DTransformation, Transformation};
 use crate::io::ext_repr::{ExtLayout, ExtPlacedItem};
+use itertools::Itertools;
 
 /// Exports a layout to an external representation.
+/// The placed items are ordered by item id and copy index, so the order is the same for every layout of an instance.
 pub fn export_layout_snapshot(layout: &LayoutSnapshot, instance: &impl Instance) -> ExtLayout {
     let ext_placed_items = layout
         .placed_items
         .values()
+        .sorted_by_key(|pi| (pi.item_id, pi.copy_idx))
         .map(|pi| {
             let item = instance.item(pi.item_id);
 
@@ -15,6 +18,7 @@ pub fn export_layout_snapshot(layout: &LayoutSnapshot, instance: &impl Instance)
 
             ExtPlacedItem {
                 item_id: pi.item_id as u64,
+                copy_idx: pi.copy_idx as u64,
                 transformation: abs_transf.into(),
             }
         })
@@ -10,6 +10,10 @@ pub struct ExtItem {
     /// Continuous rotation if not specified
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_orientations: Option<Vec<f64>>,
+    /// List of allowed rotation intervals, each as `[start, end]` (in degrees), e.g. `[[-5, 5]]` for 0° ± 5°.
+    /// Cannot be combined with `allowed_orientations`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allowed_rotation_intervals: Option<Vec<(f64, f64)>>,
     /// Shape of the item
     pub shape: ExtShape,
     /// The minimum required quality of the item.
@@ -92,6 +96,9 @@ pub struct ExtLayout {
 pub struct ExtPlacedItem {
     /// The id of the item in the instance
     pub item_id: u64,
+    /// Which copy of the item this is, together with `item_id` it identifies the placed item across solutions
+    #[serde(default)]
+    pub copy_idx: u64,
     /// The transformation applied to the item to place it in the container
     pub transformation: ExtTransformation,
 }
@@ -77,15 +77,44 @@ impl Importer {
 
         let base_quality = ext_item.min_quality;
 
-        let allowed_orientations = match ext_item.allowed_orientations.as_ref() {
-            Some(a_o) => {
+        let allowed_orientations = match (
+            ext_item.allowed_orientations.as_ref(),
+            ext_item.allowed_rotation_intervals.as_ref(),
+        ) {
+            (Some(_), Some(_)) => {
+                bail!(
+                    "Item {} defines both allowed orientations and rotation intervals",
+                    ext_item.id
+                )
+            }
+            (Some(a_o), None) => {
                 if a_o.is_empty() || (a_o.len() == 1 && a_o[0] == 0.0) {
                     RotationRange::None
                 } else {
                     RotationRange::Discrete(a_o.iter().map(|angle| angle.to_radians()).collect())
                 }
             }
-            None => RotationRange::Continuous,
+            (None, Some(a_r_i)) => {
+                if let Some((start, end)) = a_r_i.iter().find(|(start, end)| start > end) {
+                    bail!(
+                        "Item {} has an invalid rotation interval: [{start}, {end}]",
+                        ext_item.id
+                    )
+                }
+                if a_r_i.is_empty() {
+                    RotationRange::None
+                } else if a_r_i.iter().any(|(start, end)| end - start >= 360.0) {
+                    RotationRange::Continuous
+                } else {
+                    RotationRange::Intervals(
+                        a_r_i
+                            .iter()
+                            .map(|(start, end)| (start.to_radians(), end.to_radians()))
+                            .collect(),
+                    )
+                }
+            }
+            (None, None) => RotationRange::Continuous,
         };
 
         Item::new(
@@ -1,4 +1,5 @@
 use crate::Instant;
+use crate::entities::CopyIdxTracker;
 use crate::entities::Instance;
 use crate::entities::Layout;
 use crate::entities::{PItemKey, PlacedItem};
@@ -21,6 +22,8 @@ pub struct BPProblem {
     pub layouts: SlotMap<LayKey, Layout>,
     pub item_demand_qtys: Vec<usize>,
     pub bin_stock_qtys: Vec<usize>,
+    /// The copy indices of the placed items, across all layouts
+    copy_idxs: CopyIdxTracker,
 }
 
 impl BPProblem {
@@ -33,6 +36,7 @@ impl BPProblem {
             layouts: SlotMap::with_key(),
             item_demand_qtys,
             bin_stock_qtys,
+            copy_idxs: CopyIdxTracker::new(),
         }
     }
 
@@ -54,9 +58,9 @@ impl BPProblem {
         };
         let layout = &mut self.layouts[lkey];
         let item = self.instance.item(p_opt.item_id);
-        let pik = layout.place_item(item, p_opt.d_transf);
+        let pik = layout.place_item_copy(item, p_opt.d_transf, p_opt.copy_idx);
 
-        self.register_included_item(p_opt.item_id);
+        self.register_included_item(p_opt.item_id, p_opt.copy_idx);
 
         (lkey, pik)
     }
@@ -64,7 +68,7 @@ impl BPProblem {
     /// Removes an item from a layout. If the layout is empty, it will be closed.
     pub fn remove_item(&mut self, lkey: LayKey, pik: PItemKey) -> BPPlacement {
         let pi = self.layouts[lkey].remove_item(pik);
-        self.deregister_included_item(pi.item_id);
+        self.deregister_included_item(pi.item_id, pi.copy_idx);
         if self.layouts[lkey].is_empty() {
             //if layout is empty, close it
             let bin_id = self.layouts[lkey].container.id;
@@ -75,6 +79,11 @@ impl BPProblem {
         }
     }
 
+    /// Returns the lowest copy index of an item which is not in use by any of its placed copies, across all layouts.
+    pub fn next_copy_idx(&self, item_id: usize) -> usize {
+        self.copy_idxs.lowest_free(item_id)
+    }
+
     /// Creates a snapshot of the current state of the problem as a [`BPSolution`].
     pub fn save(&self) -> BPSolution {
         let layout_snapshots = self
@@ -146,6 +155,10 @@ impl BPProblem {
                     .values()
                     .for_each(|pi| self.item_demand_qtys[pi.item_id] -= 1);
             });
+
+            self.copy_idxs = CopyIdxTracker::from_placed_items(
+                self.layouts.values().flat_map(|l| l.placed_items.values()),
+            );
         }
 
         debug_assert!(problem_matches_solution(self, solution));
@@ -194,7 +207,7 @@ impl BPProblem {
         layout
             .placed_items
             .values()
-            .for_each(|pi| self.register_included_item(pi.item_id));
+            .for_each(|pi| self.register_included_item(pi.item_id, pi.copy_idx));
         self.layouts.insert(layout)
     }
 
@@ -204,15 +217,17 @@ impl BPProblem {
         layout
             .placed_items
             .values()
-            .for_each(|pi| self.deregister_included_item(pi.item_id));
+            .for_each(|pi| self.deregister_included_item(pi.item_id, pi.copy_idx));
     }
 
-    fn register_included_item(&mut self, item_id: usize) {
+    fn register_included_item(&mut self, item_id: usize, copy_idx: usize) {
         self.item_demand_qtys[item_id] -= 1;
+        self.copy_idxs.take(item_id, copy_idx);
     }
 
-    fn deregister_included_item(&mut self, item_id: usize) {
+    fn deregister_included_item(&mut self, item_id: usize, copy_idx: usize) {
         self.item_demand_qtys[item_id] += 1;
+        self.copy_idxs.release(item_id, copy_idx);
     }
 
     fn open_bin(&mut self, bin_id: usize) {
@@ -231,6 +246,8 @@ pub struct BPPlacement {
     pub layout_id: BPLayoutType,
     /// The id of the [`Item`](crate::entities::Item) to be placed
     pub item_id: usize,
+    /// Which copy of the item is placed, see [`PlacedItem::copy_idx`]
+    pub copy_idx: usize,
     /// The transformation to apply to the item when placing it
     pub d_transf: DTransformation,
 }
@@ -240,6 +257,7 @@ impl BPPlacement {
         BPPlacement {
             layout_id,
             item_id: placed_item.item_id,
+            copy_idx: placed_item.copy_idx,
             d_transf: placed_item.d_transf,
         }
     }
@@ -1,5 +1,5 @@
 use crate::Instant;
-use crate::entities::{Instance, Layout, PItemKey};
+use crate::entities::{Instance, Layout, PItemKey, PlacedItem};
 use crate::geometry::DTransformation;
 use crate::probs::spp::entities::strip::Strip;
 use crate::probs::spp::entities::{SPInstance, SPSolution};
@@ -66,21 +66,34 @@ impl SPProblem {
 
     /// Places an item according to the given `SPPlacement` in the problem.
     pub fn place_item(&mut self, placement: SPPlacement) -> PItemKey {
+        debug_assert!(placement.copy_idx < self.instance.item_qty(placement.item_id));
+        debug_assert!(
+            !self
+                .layout
+                .is_copy_placed(placement.item_id, placement.copy_idx),
+            "copy {} of item {} is already placed",
+            placement.copy_idx,
+            placement.item_id
+        );
         self.register_included_item(placement.item_id);
         let item = self.instance.item(placement.item_id);
 
-        self.layout.place_item(item, placement.d_transf)
+        self.layout
+            .place_item_copy(item, placement.d_transf, placement.copy_idx)
     }
 
     /// Removes a placed item from the strip. Returns the placement of the item.
+    /// Placing it again (at any position) keeps the identity of the copy.
     pub fn remove_item(&mut self, pkey: PItemKey) -> SPPlacement {
         let pi = self.layout.remove_item(pkey);
         self.deregister_included_item(pi.item_id);
 
-        SPPlacement {
-            item_id: pi.item_id,
-            d_transf: pi.d_transf,
-        }
+        SPPlacement::from_placed_item(&pi)
+    }
+
+    /// Returns the lowest copy index of an item which is not in use by any of its placed copies.
+    pub fn next_copy_idx(&self, item_id: usize) -> usize {
+        self.layout.next_copy_idx(item_id)
     }
 
     /// Creates a snapshot of the current state of the problem as a [`SPSolution`].
@@ -143,5 +156,17 @@ impl SPProblem {
 #[derive(Debug, Clone, Copy)]
 pub struct SPPlacement {
     pub item_id: usize,
+    /// Which copy of the item is placed, see [`PlacedItem::copy_idx`]
+    pub copy_idx: usize,
     pub d_transf: DTransformation,
 }
+
+impl SPPlacement {
+    pub fn from_placed_item(placed_item: &PlacedItem) -> Self {
+        SPPlacement {
+            item_id: placed_item.item_id,
+            copy_idx: placed_item.copy_idx,
+            d_transf: placed_item.d_transf,
+        }
+    }
+}
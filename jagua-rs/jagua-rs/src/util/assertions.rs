@@ -267,8 +267,8 @@ pub fn print_layout(layout: &Layout) {
         };
 
         println!(
-            "layout.place_item(instance.item({}), {});",
-            pi.item_id, transformation_str
+            "layout.place_item_copy(instance.item({}), {}, {});",
+            pi.item_id, transformation_str, pi.copy_idx
         );
     }
 }
@@ -150,10 +150,7 @@ fn cde_update_bench(c: &mut Criterion) {
                         .choose(&mut rng)
                         .expect("No items in layout");
 
-                    let p_opt = SPPlacement {
-                        item_id: pi.item_id,
-                        d_transf: pi.d_transf,
-                    };
+                    let p_opt = SPPlacement::from_placed_item(pi);
 
                     //println!("Removing item with id: {}\n", pi_uid.item_id);
                     problem.remove_item(pkey);
@@ -47,11 +47,7 @@ pub fn create_lbf_problem(
     let p_opts = placed_items_to_remove
         .iter()
         .map(|k| {
-            let pi = &problem.layout.placed_items[*k];
-            SPPlacement {
-                item_id: pi.item_id,
-                d_transf: pi.d_transf,
-            }
+            SPPlacement::from_placed_item(&problem.layout.placed_items[*k])
         })
         .collect_vec();
 
@@ -132,6 +132,7 @@ fn search_layouts(
             return Some(BPPlacement {
                 layout_id,
                 item_id: item.id,
+                copy_idx: problem.next_copy_idx(item.id),
                 d_transf,
             });
         }
@@ -69,6 +69,7 @@ impl LBFOptimizerSP {
                     Some((d_transf, _)) => {
                         self.problem.place_item(SPPlacement {
                             item_id: item.id,
+                            copy_idx: self.problem.next_copy_idx(item.id),
                             d_transf,
                         });
                         info!(
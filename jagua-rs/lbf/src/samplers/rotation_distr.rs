@@ -6,6 +6,7 @@ use rand_distr::Uniform;
 use std::f64::consts::PI;
 
 use jagua_rs::entities::Item;
+use jagua_rs::geometry::closest_rotation_in_intervals;
 use jagua_rs::geometry::geo_enums::RotationRange;
 
 /// Samples a rotation (radians).
@@ -13,17 +14,21 @@ pub trait RotationSampler {
     fn sample(&self, rng: &mut impl Rng) -> f64;
 }
 
-/// Samples a rotation from a uniform distribution over a given range or a discrete set of rotations.
+/// Samples a rotation from a uniform distribution over a given range, a set of intervals or a discrete set of rotations.
 pub enum UniformRotDistr {
     Range(Uniform<f64>),
+    /// Uniform over the total length of the intervals
+    Intervals(Vec<(f64, f64)>),
     Discrete(Vec<f64>),
     None,
 }
 
 /// Samples a rotation from a normal distribution over a given range or a discrete set of rotations.
 /// In case of discrete rotations the mean is always returned.
+/// In case of rotation intervals the sample is clamped to the closest interval.
 pub enum NormalRotDistr {
     Range(Normal<f64>),
+    Intervals(Normal<f64>, Vec<(f64, f64)>),
     Discrete(f64),
     None,
 }
@@ -36,6 +41,7 @@ impl UniformRotDistr {
                 UniformRotDistr::Range(Uniform::new(0.0, 2.0 * PI).unwrap())
             }
             RotationRange::Discrete(a_o) => UniformRotDistr::Discrete(a_o.clone()),
+            RotationRange::Intervals(a_r_i) => UniformRotDistr::Intervals(a_r_i.clone()),
         }
     }
 
@@ -43,6 +49,17 @@ impl UniformRotDistr {
         match self {
             UniformRotDistr::None => 0.0,
             UniformRotDistr::Range(u) => u.sample(rng),
+            UniformRotDistr::Intervals(a_r_i) => {
+                let total_length = a_r_i.iter().map(|(start, end)| end - start).sum::<f64>();
+                let mut remainder = rng.random_range(0.0..=total_length);
+                for (start, end) in a_r_i {
+                    if remainder <= end - start {
+                        return start + remainder;
+                    }
+                    remainder -= end - start;
+                }
+                a_r_i.last().unwrap().1
+            }
             UniformRotDistr::Discrete(a_o) => *a_o.choose(rng).unwrap(),
         }
     }
@@ -54,12 +71,15 @@ impl NormalRotDistr {
             RotationRange::None => NormalRotDistr::None,
             RotationRange::Continuous => NormalRotDistr::Range(Normal::new(r_ref, stddev).unwrap()),
             RotationRange::Discrete(_) => NormalRotDistr::Discrete(r_ref),
+            RotationRange::Intervals(a_r_i) => {
+                NormalRotDistr::Intervals(Normal::new(r_ref, stddev).unwrap(), a_r_i.clone())
+            }
         }
     }
 
     pub fn set_mean(&mut self, mean: f64) {
         match self {
-            NormalRotDistr::Range(n) => {
+            NormalRotDistr::Range(n) | NormalRotDistr::Intervals(n, _) => {
                 *n = Normal::new(mean, n.std_dev()).unwrap();
             }
             NormalRotDistr::Discrete(_) | NormalRotDistr::None => {}
@@ -68,7 +88,7 @@ impl NormalRotDistr {
 
     pub fn set_stddev(&mut self, stddev: f64) {
         match self {
-            NormalRotDistr::Range(n) => {
+            NormalRotDistr::Range(n) | NormalRotDistr::Intervals(n, _) => {
                 *n = Normal::new(n.mean(), stddev).unwrap();
             }
             NormalRotDistr::Discrete(_) | NormalRotDistr::None => {}
@@ -79,6 +99,9 @@ impl NormalRotDistr {
         match self {
             NormalRotDistr::None => 0.0,
             NormalRotDistr::Range(n) => n.sample(rng),
+            NormalRotDistr::Intervals(n, a_r_i) => {
+                closest_rotation_in_intervals(n.sample(rng), a_r_i)
+            }
             NormalRotDistr::Discrete(r) => *r,
         }
     }
@@ -0,0 +1,280 @@
+use clap::Args;
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+use log::{info, warn, error};
+use rand::SeedableRng;
+use sparrow::config::*;
+use sparrow::optimizer::optimize;
+use sparrow::optimizer::separator::ContainerMode;
+use sparrow::util::io;
+use sparrow::util::io::{MainCli, SPOutput};
+use std::fs;
+use std::path::{Path, PathBuf};
+use jagua_rs::io::import::Importer;
+use sparrow::EPOCH;
+use sparrow::sample::search::SampleConfig;
+
+use anyhow::{bail, Result};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use sparrow::consts::{BATCH_COMPRESS_TIME_LIMIT, BATCH_EXPLORE_TIME_LIMIT, DEFAULT_VALIDATION_TOLERANCE, LOG_LEVEL_FILTER_RELEASE};
+use sparrow::util::svg_exporter::SvgExporter;
+use sparrow::util::rounding::round_output;
+use sparrow::util::validator::validate;
+use sparrow::util::event_stream::EventStreamListener;
+use sparrow::util::live_server::LiveServer;
+use sparrow::util::replay::ReplayRecorder;
+use std::sync::Arc;
+use sparrow::util::ctrlc_terminator::CtrlCTerminator;
+use sparrow::util::run_controller::RunController;
+use sparrow::util::early_terminator::EarlyTerminator;
+use std::panic;
+use rand::Rng;
+use crate::cli::{apply_early_termination, apply_search_options, apply_time_limits, early_termination_config, initial_square_side, item_rotation_configs, set_container_mode, OUTPUT_DIR};
+
+
+#[derive(Args)]
+pub struct BatchArgs {
+    #[clap(flatten)]
+    pub main_args: MainCli,
+
+    #[clap(long, default_value = "10")]
+    pub start: usize,
+
+    #[clap(long, default_value = "50")]
+    pub end: usize,
+
+    #[clap(long, default_value = "1")]
+    pub step_qty: usize,
+
+    /// Number of workers per job, all available cores if not set
+    #[clap(long)]
+    pub force_cores: Option<usize>,
+
+    /// Tolerance of the validation of every job's solution, see [`validate`]
+    #[clap(long, default_value_t = DEFAULT_VALIDATION_TOLERANCE)]
+    pub tolerance: f64,
+}
+
+pub fn run(args: BatchArgs) -> Result<()> {
+    // 1. SETUP
+    let total_cpu_cores = std::thread::available_parallelism().unwrap().get();
+
+    fs::create_dir_all(OUTPUT_DIR)?;
+    let log_file_path = format!("{}/log_master.txt", OUTPUT_DIR);
+    
+    //keep stdout clean if the event stream is written to it
+    let log_to_stderr = args.main_args.events.as_deref() == Some("-");
+    match cfg!(debug_assertions) {
+        true => io::init_logger(log::LevelFilter::Debug, Path::new(&log_file_path), log_to_stderr)?,
+        false => io::init_logger(LOG_LEVEL_FILTER_RELEASE, Path::new(&log_file_path), log_to_stderr)?,
+    }
+
+    // 2. RESOURCES
+    // Jobs run one after the other, so every job can use all cores
+    let n_workers = args.force_cores.unwrap_or(total_cpu_cores);
+    
+    info!("[MASTER] Mode: SEQUENTIAL BATCH (Square Constraint). Total Cores: {}. Workers per Task: {}.", 
+        total_cpu_cores, n_workers);
+
+    let input_file_path = &args.main_args.input;
+    let base_ext_instance = io::read_spp_instance_json(Path::new(&input_file_path))?;
+
+    let controller = RunController::new(CtrlCTerminator::new(), args.main_args.control.as_ref().map(PathBuf::from))?;
+    let mut terminator = EarlyTerminator::new(controller, early_termination_config(&args.main_args));
+    let live_server = args.main_args.live_port.map(LiveServer::start).transpose()?;
+    //a single stream for all jobs, every event is tagged with the job it belongs to
+    let mut event_stream = args.main_args.events.as_deref().map(EventStreamListener::open).transpose()?;
+
+    // 3. SEQUENTIAL LOOP OVER THE QUANTITIES
+    let mut qty = args.start;
+    
+    while qty <= args.end {
+        if terminator.inner.stop_requested() {
+            warn!("[MASTER] stop requested, skipping remaining jobs from {} items", qty);
+            break;
+        }
+        info!("\n========================================");
+        info!("[MASTER] Starting Job: {} items", qty);
+        info!("========================================");
+
+        // Every job runs on the main thread
+        if let Err(e) = solve_single_task(
+            qty, 
+            n_workers, 
+            base_ext_instance.clone(),
+            &args,
+            live_server.as_ref(),
+            &mut event_stream,
+            &mut terminator
+        ) {
+            error!("[MASTER] Job {} failed: {}", qty, e);
+        } else {
+            info!("[MASTER] Finished Job: {} items.", qty);
+        }
+
+        qty += args.step_qty;
+    }
+
+    info!("[MASTER] All jobs completed.");
+    Ok(())
+}
+
+fn solve_single_task(
+    target_qty: usize, 
+    n_workers: usize, 
+    mut ext_instance: ExtSPInstance,
+    batch_args: &BatchArgs,
+    live_server: Option<&Arc<LiveServer>>,
+    event_stream: &mut Option<EventStreamListener>,
+    terminator: &mut EarlyTerminator<RunController<CtrlCTerminator>>
+) -> Result<()> {
+    let (args, tolerance) = (&batch_args.main_args, batch_args.tolerance);
+    
+    // 1. SET THE QUANTITY OF THE ITEM
+    if let Some(first_item) = ext_instance.items.first_mut() {
+        first_item.demand = target_qty as u64;
+    } else {
+        bail!("Input file has no items!");
+    }
+
+    // output directory of the job
+    let task_dir = format!("{}/qty_{}", OUTPUT_DIR, target_qty);
+    fs::create_dir_all(&task_dir)?;
+
+    // 2. "ULTRA" CONFIG (HEAVY SETTINGS FOR BATCH RUNS)
+    let mut config = DEFAULT_SPARROW_CONFIG;
+    config.rng_seed = args.rng_seed
+        .map(|s| s as usize)
+        .or_else(|| Some(rand::rng().random::<u64>() as usize));
+    let master_seed = config.rng_seed.unwrap() as u64;
+
+    // A. Use all workers assigned to the job
+    config.expl_cfg.separator_config.n_workers = n_workers;
+    config.cmpr_cfg.separator_config.n_workers = n_workers;
+
+    // B. Ultra sampling (many samples, to find the best possible solution)
+    let ultra_sample_config = SampleConfig {
+        n_container_samples: 200, 
+        n_focussed_samples: 100,  
+        n_coord_descents: 20,     
+        ..config.expl_cfg.separator_config.sample_config
+    };
+    config.expl_cfg.separator_config.sample_config = ultra_sample_config;
+    config.cmpr_cfg.separator_config.sample_config = ultra_sample_config;
+
+    // C. Persistence
+    config.expl_cfg.separator_config.iter_no_imprv_limit = 1000;
+    config.cmpr_cfg.separator_config.iter_no_imprv_limit = 1000;
+
+    config.expl_cfg.separator_config.strike_limit = 20;
+
+    config.cmpr_cfg.shrink_decay = ShrinkDecayStrategy::FailureBased(0.99);
+    
+    // D. Geometry Precision
+    config.poly_simpl_tolerance = Some(0.00001);
+
+    // E. Time limits: generous defaults for shrinking the square, overridden by the arguments (-t or -e and -c)
+    config.expl_cfg.time_limit = BATCH_EXPLORE_TIME_LIMIT;
+    config.cmpr_cfg.time_limit = BATCH_COMPRESS_TIME_LIMIT;
+    apply_time_limits(&mut config, args);
+    apply_early_termination(&mut config, args);
+    apply_search_options(&mut config, args)?;
+    set_container_mode(&mut config, ContainerMode::Square);
+
+    // 3. PREPARE THE INSTANCE
+    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+    
+    // Import the items to measure their exact area
+    let base_instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+
+    let n = target_qty as f64;
+    // Start from a square with a density of SQUARE_INIT_DENSITY, large enough to contain every item
+    let start_size = initial_square_side(&base_instance);
+    info!("[Job {}] Start Square Size: {:.2}", target_qty, start_size);
+
+    // Set the initial size of the square for the instance
+    let mut current_ext_instance = ext_instance.clone();
+    current_ext_instance.strip_height = start_size;
+
+    let instance_struct = jagua_rs::probs::spp::io::import(&importer, &current_ext_instance)?;
+
+    // 4. OPTIMIZE (SINGLE RUN - SQUARE CONSTRAINT)
+    // No binary search over the size, the optimizer shrinks the square itself
+    let rng = Xoshiro256PlusPlus::seed_from_u64(master_seed);
+    
+    let final_svg_path = Some(format!("{}/result.svg", task_dir));
+    //no intermediate solutions are written, so there is nothing to overlay a heatmap on
+    let final_exporter = SvgExporter::new(final_svg_path, None, None, None);
+    if let Some(event_stream) = event_stream.as_mut() {
+        event_stream.start_job(format!("qty_{}", target_qty));
+    }
+    let live_view = live_server.map(|s| s.listener(format!("qty_{}", target_qty)));
+    let recorder = args.record.then(|| ReplayRecorder::new(format!("{}/recording.json", task_dir), &current_ext_instance.name));
+    let mut sol_listener = (final_exporter, (event_stream, (live_view, (recorder, (terminator.listener(), terminator.inner.listener(&task_dir))))));
+
+    // catch_unwind ensures a panicking job does not take down the whole batch
+    let item_rot_configs = item_rotation_configs(&config, args, instance_struct.items.len())?;
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        optimize(
+            instance_struct.clone(),
+            rng,
+            &mut sol_listener,
+            terminator,
+            &config.expl_cfg,
+            &config.cmpr_cfg,
+            &item_rot_configs,
+        )
+    }));
+
+    match result {
+        Ok(result) => {
+            let final_solution = result.solution;
+            let final_size = final_solution.strip_width();
+            let final_score = final_size * final_size / n;
+            info!("[Job {}] SUCCESS.", target_qty);
+            info!("Final Square Side: {:.10}", final_size);
+            info!("Final Score: {:.10}", final_score);
+
+            // the exported instance has the size of the final square
+            let mut final_snapshot = current_ext_instance.clone();
+            final_snapshot.strip_height = final_size;
+
+            let mut output_struct = SPOutput {
+                instance: final_snapshot,
+                solution: jagua_rs::probs::spp::io::export(&instance_struct, &final_solution, *EPOCH),
+                rotation_histograms: result.rot_model.learned_histograms(),
+            };
+
+            //if the rounding fails, the job still writes its (unrounded) solution
+            let mut csv_decimals = args.decimals;
+            if let Some(decimals) = args.decimals {
+                match round_output(&output_struct, ContainerMode::Square, decimals, tolerance) {
+                    Ok((rounded, report)) => {
+                        info!("[Job {}] {}", target_qty, report);
+                        output_struct = rounded;
+                    }
+                    Err(e) => {
+                        error!("[Job {}] rounding failed, writing the unrounded solution: {}", target_qty, e);
+                        csv_decimals = None;
+                    }
+                }
+            }
+
+            //check the exported solution against the original shapes, independently of the optimizer
+            let container = (output_struct.solution.strip_width, output_struct.instance.strip_height);
+            let report = validate(&output_struct.instance, &output_struct.solution.layout.placed_items, Some(container), tolerance)?;
+            match report.is_feasible() {
+                true => info!("[Job {}] validation: {}", target_qty, report),
+                false => warn!("[Job {}] validation: {}", target_qty, report),
+            }
+
+            //the rows of all jobs are appended to a single CSV
+            let csv_path = format!("{}/result.csv", OUTPUT_DIR);
+            io::write_csv(&output_struct.solution, Path::new(&csv_path), csv_decimals)?;
+        }
+        Err(_) => {
+            error!("[Job {}] FAILED due to panic.", target_qty);
+        }
+    }
+
+    Ok(())
+}
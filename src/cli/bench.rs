@@ -1,13 +1,11 @@
-extern crate core;
-use sparrow::util::terminator::Terminator;
+use clap::{Args, ValueEnum};
 
 use ordered_float::OrderedFloat;
-use rand::{Rng, RngCore, SeedableRng};
+use rand::{Rng, SeedableRng};
 use sparrow::config::*;
-use sparrow::optimizer::lbf::LBFBuilder;
-use sparrow::optimizer::separator::Separator;
+use sparrow::optimizer::optimize;
+use sparrow::optimizer::separator::ContainerMode;
 use sparrow::util::io;
-use std::env::args;
 use std::fs;
 use std::path::Path;
 use std::time::Duration;
@@ -17,29 +15,71 @@ use anyhow::Result;
 use jagua_rs::io::import::Importer;
 use jagua_rs::io::svg::s_layout_to_svg;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use sparrow::consts::{DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DRAW_OPTIONS, LBF_SAMPLE_CONFIG};
-use sparrow::optimizer::compress::compression_phase;
-use sparrow::optimizer::explore::exploration_phase;
+use sparrow::consts::{DEFAULT_ADAPTIVE_BUDGET_CONFIG, DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DRAW_OPTIONS, LS_FOCUSSED_SAMPLER};
+use sparrow::sample::search::FocussedSampler;
 use sparrow::util::listener::DummySolListener;
 use sparrow::util::terminator::BasicTerminator;
+use crate::cli::{set_container_mode, OUTPUT_DIR};
 
-pub const OUTPUT_DIR: &str = "output";
+#[derive(Args)]
+pub struct BenchArgs {
+    #[arg(short = 'i', long, help = "Path to the input JSON file")]
+    pub input: String,
 
-fn main() -> Result<()> {
+    #[arg(short = 't', long, help = "Time limit of every run (in seconds)")]
+    pub time: u64,
+
+    #[arg(short = 'r', long, help = "Total number of runs")]
+    pub runs: usize,
+
+    /// Focussed sampler of the separator, to compare strategies
+    #[arg(long, value_enum, default_value_t = BenchSampler::Uniform, help = "Focussed sampler of the separator")]
+    pub sampler: BenchSampler,
+
+    #[arg(short = 's', long, help = "Fixed seed for the random number generator")]
+    pub rng_seed: Option<u64>,
+
+    #[arg(long, help = "Pack the items into a square instead of a strip")]
+    pub square: bool,
+
+    /// Adaptive sample budgets in both phases, to compare them against the fixed budgets
+    #[arg(long, help = "Adapt the sample budgets of every item to where its improvements come from")]
+    pub adaptive_budget: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum BenchSampler {
+    Uniform,
+    Ls,
+}
+
+pub fn run(args: BenchArgs) -> Result<()> {
     let mut config = DEFAULT_SPARROW_CONFIG;
 
-    //the input file is the first argument
-    let input_file_path = args().nth(1).expect("first argument must be the input file");
-    let time_limit: Duration = args().nth(2).expect("second argument must be the time limit [s]")
-        .parse::<u64>().map(|s| Duration::from_secs(s))
-        .expect("second argument must be the time limit [s]");
-    let n_runs_total = args().nth(3).expect("third argument must be the number of runs")
-        .parse().expect("third argument must be the number of runs");
+    let input_file_path = args.input;
+    let time_limit = Duration::from_secs(args.time);
+    let n_runs_total = args.runs;
+    let focussed_sampler = match args.sampler {
+        BenchSampler::Uniform => FocussedSampler::UniformBBox,
+        BenchSampler::Ls => LS_FOCUSSED_SAMPLER,
+    };
+    config.rng_seed = args.rng_seed.map(|s| s as usize);
+    set_container_mode(&mut config, match args.square {
+        true => ContainerMode::Square,
+        false => ContainerMode::Strip,
+    });
+    let adaptive_budget = args.adaptive_budget.then_some(DEFAULT_ADAPTIVE_BUDGET_CONFIG);
+    for sep_config in [&mut config.expl_cfg.separator_config, &mut config.cmpr_cfg.separator_config] {
+        sep_config.sample_config.focussed_sampler = focussed_sampler;
+        sep_config.sample_config.adaptive_budget = adaptive_budget;
+    }
 
     fs::create_dir_all(OUTPUT_DIR).expect("could not create output directory");
 
     println!("[BENCH] git commit hash: {}", get_git_commit_hash());
     println!("[BENCH] system time: {}", jiff::Timestamp::now());
+    println!("[BENCH] focussed sampler: {:?}", focussed_sampler);
+    println!("[BENCH] adaptive budget: {:?}", adaptive_budget);
 
     let mut rng = match config.rng_seed {
         Some(seed) => {
@@ -56,7 +96,8 @@ fn main() -> Result<()> {
     config.expl_cfg.time_limit = time_limit.mul_f64(DEFAULT_EXPLORE_TIME_RATIO);
     config.cmpr_cfg.time_limit = time_limit.mul_f64(DEFAULT_COMPRESS_TIME_RATIO);
 
-    let n_runs_per_iter = (num_cpus::get_physical() / config.expl_cfg.separator_config.n_workers).min(n_runs_total);
+    //at least one run per batch, even on machines with fewer cores than workers per run
+    let n_runs_per_iter = (num_cpus::get_physical() / config.expl_cfg.separator_config.n_workers).min(n_runs_total).max(1);
     let n_batches = (n_runs_total as f64 / n_runs_per_iter as f64).ceil() as usize;
 
     let ext_instance = io::read_spp_instance_json(Path::new(&input_file_path))?;
@@ -80,30 +121,20 @@ fn main() -> Result<()> {
             for (j, sol_slice) in iter_solutions.iter_mut().enumerate() {
                 let bench_idx = i * n_runs_per_iter + j;
                 let instance = instance.clone();
-                let mut rng = Xoshiro256PlusPlus::seed_from_u64(rng.random());
+                let rng = Xoshiro256PlusPlus::seed_from_u64(rng.random());
                 let mut terminator = BasicTerminator::new();
 
                 s.spawn(move |_| {
-                    let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
-                    let builder = LBFBuilder::new(instance.clone(), next_rng(), LBF_SAMPLE_CONFIG).construct();
-                    let mut expl_separator = Separator::new(builder.instance, builder.prob, next_rng(), config.expl_cfg.separator_config);
-
-                    terminator.new_timeout(config.expl_cfg.time_limit);
-                    let solutions = exploration_phase(&instance, &mut expl_separator, &mut DummySolListener, &terminator, &config.expl_cfg);
-                    let final_explore_sol = solutions.last().expect("no solutions found during exploration");
-
-                    let start_comp = Instant::now();
-
-                    terminator.new_timeout(config.cmpr_cfg.time_limit);
-                    let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), config.cmpr_cfg.separator_config);
-                    let cmpr_sol = compression_phase(&instance, &mut cmpr_separator, final_explore_sol, &mut DummySolListener, &terminator, &config.cmpr_cfg);
+                    let start = Instant::now();
+                    let result = optimize(instance.clone(), rng, &mut DummySolListener, &mut terminator, &config.expl_cfg, &config.cmpr_cfg, &[]);
+                    let (expl_sol, cmpr_sol) = (result.explore_solution, result.solution);
 
-                    println!("[BENCH] [id:{:>3}] finished, expl: {:.3}% ({}s), cmpr: {:.3}% (+{:.3}%) ({}s)",
+                    println!("[BENCH] [id:{:>3}] finished, expl: {:.3}%, cmpr: {:.3}% (+{:.3}%) ({}s)",
                              bench_idx,
-                             final_explore_sol.density(&instance) * 100.0, time_limit.mul_f64(DEFAULT_EXPLORE_TIME_RATIO).as_secs(),
+                             expl_sol.density(&instance) * 100.0,
                              cmpr_sol.density(&instance) * 100.0,
-                             cmpr_sol.density(&instance) * 100.0 - final_explore_sol.density(&instance) * 100.0,
-                             start_comp.elapsed().as_secs()
+                             cmpr_sol.density(&instance) * 100.0 - expl_sol.density(&instance) * 100.0,
+                             start.elapsed().as_secs()
                     );
 
                     io::write_svg(
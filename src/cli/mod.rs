@@ -0,0 +1,153 @@
+use anyhow::{ensure, Result};
+use clap::{Parser, Subcommand};
+use sparrow::config::SparrowConfig;
+use sparrow::consts::{DEFAULT_ADAPTIVE_BUDGET_CONFIG, DEFAULT_WEIGHT_CONFIG, DEFAULT_COMPRESS_TIME_RATIO, DEFAULT_EXPLORE_TIME_RATIO, DEFAULT_FAIL_DECAY_RATIO_CMPR, DEFAULT_MAX_CONSEQ_FAILS_EXPL, DEFAULT_POLISH_CONFIG, DEFAULT_ROTATION_LEARNING_CONFIG, DEFAULT_RUIN_RECREATE_CONFIG, SQUARE_INIT_DENSITY};
+use sparrow::config::ShrinkDecayStrategy;
+use sparrow::optimizer::separator::{AcceptanceCriterion, ContainerMode};
+use sparrow::quantify::tracker::{WeightConfig, WeightSchedule, WeightSelection};
+use sparrow::sample::rotation::RotationConfig;
+use sparrow::util::early_terminator::{EarlyTerminationConfig, StagnationLimit, Target};
+use sparrow::util::heatmap::HeatmapOptions;
+use jagua_rs::probs::spp::entities::SPInstance;
+use sparrow::util::io::MainCli;
+use std::time::Duration;
+
+pub mod batch;
+pub mod bench;
+pub mod render;
+pub mod replay;
+pub mod solve;
+pub mod validate;
+
+pub const OUTPUT_DIR: &str = "output";
+
+#[derive(Parser)]
+#[command(name = "sparrow", about = "State-of-the-art nesting heuristic for 2D irregular strip packing problems")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Solve a single instance, as a strip or square packing problem
+    Solve(solve::SolveArgs),
+    /// Solve a range of quantities of the first item of an instance, as square packing problems
+    Batch(batch::BatchArgs),
+    /// Solve an instance repeatedly with different seeds and print statistics of the results
+    Bench(bench::BenchArgs),
+    /// Check whether a solution file is feasible
+    Validate(validate::ValidateArgs),
+    /// Render a solution file to SVG
+    Render(render::RenderArgs),
+    /// Replay a recording as an animated SVG or an HTML player
+    Replay(replay::ReplayArgs),
+}
+
+/// Applies the time limits of the arguments (-t or -e and -c) to the config, if provided
+pub fn apply_time_limits(config: &mut SparrowConfig, args: &MainCli) {
+    if let Some(gt) = args.global_time {
+        config.expl_cfg.time_limit = Duration::from_secs(gt).mul_f64(DEFAULT_EXPLORE_TIME_RATIO);
+        config.cmpr_cfg.time_limit = Duration::from_secs(gt).mul_f64(DEFAULT_COMPRESS_TIME_RATIO);
+    }
+    if let (Some(et), Some(ct)) = (args.exploration, args.compression) {
+        config.expl_cfg.time_limit = Duration::from_secs(et);
+        config.cmpr_cfg.time_limit = Duration::from_secs(ct);
+    }
+}
+
+/// With early termination (-x), exploration ends after a number of consecutive failed attempts and the compression shrinks by decaying steps
+pub fn apply_early_termination(config: &mut SparrowConfig, args: &MainCli) {
+    if args.early_termination {
+        config.expl_cfg.max_conseq_failed_attempts = Some(DEFAULT_MAX_CONSEQ_FAILS_EXPL);
+        config.cmpr_cfg.shrink_decay = ShrinkDecayStrategy::FailureBased(DEFAULT_FAIL_DECAY_RATIO_CMPR);
+    }
+}
+
+/// Applies the options of the search to the configs of both phases
+pub fn apply_search_options(config: &mut SparrowConfig, args: &MainCli) -> Result<()> {
+    let weights = weight_config(args)?;
+    for sep_config in [&mut config.expl_cfg.separator_config, &mut config.cmpr_cfg.separator_config] {
+        sep_config.weights = weights;
+        if args.annealing {
+            sep_config.acceptance = AcceptanceCriterion::Annealing { t_init: args.t_init, t_decay: args.t_decay };
+        }
+        if args.ruin_recreate {
+            sep_config.ruin_recreate = Some(DEFAULT_RUIN_RECREATE_CONFIG);
+        }
+        if args.adaptive_budget {
+            sep_config.sample_config.adaptive_budget = Some(DEFAULT_ADAPTIVE_BUDGET_CONFIG);
+        }
+        if args.learn_rotations {
+            sep_config.sample_config.rotation.learning = Some(DEFAULT_ROTATION_LEARNING_CONFIG);
+        }
+    }
+    if args.polish {
+        config.cmpr_cfg.polish = Some(DEFAULT_POLISH_CONFIG);
+    }
+    Ok(())
+}
+
+/// Weight dynamics of the guided local search set by the `--gls-*` options, the default ones if none are set
+pub fn weight_config(args: &MainCli) -> Result<WeightConfig> {
+    ensure!(args.gls_min_inc >= 1.0 && args.gls_max_inc >= args.gls_min_inc, "weight multipliers should satisfy 1.0 <= --gls-min-inc <= --gls-max-inc");
+    ensure!(args.gls_decay > 0.0 && args.gls_decay <= 1.0, "--gls-decay should be in ]0.0, 1.0]");
+    if let Some(max_weight) = args.gls_max_weight {
+        ensure!(max_weight >= DEFAULT_WEIGHT_CONFIG.min_weight, "--gls-max-weight should be at least the minimum weight ({})", DEFAULT_WEIGHT_CONFIG.min_weight);
+    }
+    Ok(WeightConfig {
+        pair: WeightSchedule {
+            max_inc_ratio: args.gls_max_inc,
+            min_inc_ratio: args.gls_min_inc,
+            decay: args.gls_decay,
+        },
+        selection: args.gls_top_k.map_or(WeightSelection::AllColliding, WeightSelection::TopK),
+        max_weight: args.gls_max_weight,
+        ..DEFAULT_WEIGHT_CONFIG
+    })
+}
+
+/// Rotation sampling of the items with a number of samples set by `--rotation-samples`, based on the rotation config of the exploration
+pub fn item_rotation_configs(config: &SparrowConfig, args: &MainCli, n_items: usize) -> Result<Vec<(usize, RotationConfig)>> {
+    args.rotation_samples.iter()
+        .map(|&(item_id, n_samples)| {
+            ensure!(item_id < n_items, "--rotation-samples: item {item_id} is not part of the instance ({n_items} items)");
+            Ok((item_id, RotationConfig { n_samples, ..config.expl_cfg.separator_config.sample_config.rotation }))
+        })
+        .collect()
+}
+
+/// Heatmap of the intermediate infeasible solutions, if enabled by `--heatmap`
+pub fn heatmap_options(args: &MainCli) -> Option<HeatmapOptions> {
+    args.heatmap.map(|metric| HeatmapOptions { metric, pair_weight_links: true })
+}
+
+/// Side of the initial square container: large enough to contain every item, with a density of [`SQUARE_INIT_DENSITY`]
+pub fn initial_square_side(instance: &SPInstance) -> f64 {
+    let max_diameter = instance.items.iter().map(|(item, _)| item.shape_cd.diameter).fold(0.0, f64::max);
+    f64::max((instance.item_area() / SQUARE_INIT_DENSITY).sqrt(), max_diameter)
+}
+
+pub fn set_container_mode(config: &mut SparrowConfig, mode: ContainerMode) {
+    config.expl_cfg.separator_config.container = mode;
+    config.cmpr_cfg.separator_config.container = mode;
+}
+
+/// Target and stagnation conditions of the early termination (-x), no conditions if it is disabled
+pub fn early_termination_config(args: &MainCli) -> EarlyTerminationConfig {
+    if !args.early_termination {
+        return EarlyTerminationConfig::default();
+    }
+    let target = args.target_size.map(Target::Size)
+        .or(args.target_density.map(Target::Density))
+        .or(args.target_score.map(Target::Score));
+    let stagnation = StagnationLimit {
+        time: args.stagnation_secs.map(Duration::from_secs),
+        attempts: args.stagnation_attempts,
+    };
+    EarlyTerminationConfig {
+        target,
+        expl_stagnation: stagnation,
+        cmpr_stagnation: stagnation,
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+use anyhow::Result;
+use clap::Args;
+use jagua_rs::io::import::Importer;
+use jagua_rs::io::svg::s_layout_to_svg;
+use log::Level;
+use sparrow::config::DEFAULT_SPARROW_CONFIG;
+use sparrow::consts::DRAW_OPTIONS;
+use sparrow::quantify::tracker::CollisionTracker;
+use sparrow::util::heatmap::{add_heatmap, HeatmapMetric, HeatmapOptions};
+use sparrow::util::io;
+
+#[derive(Args)]
+pub struct RenderArgs {
+    #[arg(short = 'i', long, help = "Path to the solution JSON file (as written by solve)")]
+    pub input: String,
+
+    #[arg(short = 'o', long, help = "Path to write the SVG file to")]
+    pub output: String,
+
+    /// Overlays the collisions of the solution, all weights are at their initial value
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "loss", value_name = "METRIC", help = "Overlay a heatmap of the collisions in the solution")]
+    pub heatmap: Option<HeatmapMetric>,
+}
+
+pub fn run(args: RenderArgs) -> Result<()> {
+    let output = io::read_spp_output_json(Path::new(&args.input))?;
+
+    //render the original shapes of the items
+    let importer = Importer::new(DEFAULT_SPARROW_CONFIG.cde_config, None, None, None);
+    let instance = jagua_rs::probs::spp::io::import(&importer, &output.instance)?;
+    let layout = io::import_layout(&instance, &output.solution)?;
+
+    let title = format!("{}_{:.3}", output.instance.name, output.solution.strip_width);
+    let snapshot = layout.save();
+    let mut svg = s_layout_to_svg(&snapshot, &instance, DRAW_OPTIONS, &title);
+    if let Some(metric) = args.heatmap {
+        let ct = CollisionTracker::new(&layout);
+        svg = add_heatmap(svg, &snapshot, &ct, HeatmapOptions { metric, pair_weight_links: false });
+    }
+    io::write_svg(&svg, Path::new(&args.output), Level::Info)?;
+    println!("[RENDER] solution written to {}", args.output);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use jagua_rs::io::import::Importer;
+use sparrow::config::DEFAULT_SPARROW_CONFIG;
+use sparrow::util::io;
+use sparrow::util::replay::{animated_svg, html_player, read_recording, render_frames};
+use std::fs;
+use std::path::Path;
+
+/// Seconds every frame is shown in an animated SVG, unless provided
+const DEFAULT_FRAME_DURATION_S: f64 = 0.2;
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    #[arg(short = 'i', long, help = "Path to the instance JSON file of the recording")]
+    pub instance: String,
+
+    #[arg(short = 'r', long, help = "Path to the recording JSON file")]
+    pub recording: String,
+
+    #[arg(short = 'o', long, help = "Path to write the replay to (.svg or .html)")]
+    pub output: String,
+
+    #[arg(long, default_value_t = DEFAULT_FRAME_DURATION_S, help = "Duration of every frame of an animated SVG (in seconds)")]
+    pub frame_duration: f64,
+}
+
+pub fn run(args: ReplayArgs) -> Result<()> {
+    let config = DEFAULT_SPARROW_CONFIG;
+
+    let ext_instance = io::read_spp_instance_json(Path::new(&args.instance))?;
+    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+    let instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+
+    let recording = read_recording(Path::new(&args.recording))?;
+    if recording.frames.is_empty() {
+        bail!("recording contains no frames");
+    }
+    println!("[REPLAY] rendering {} frames of {}", recording.frames.len(), recording.instance_name);
+    let frames = render_frames(&recording, &instance)?;
+
+    let output = match Path::new(&args.output).extension().and_then(|e| e.to_str()) {
+        Some("svg") => animated_svg(&frames, args.frame_duration),
+        Some("html") => html_player(&recording, &frames)?,
+        _ => bail!("output file must have an .svg or .html extension: {}", args.output),
+    };
+    fs::write(&args.output, output)?;
+    println!("[REPLAY] replay written to {}", args.output);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use clap::Args;
+use jagua_rs::io::import::Importer;
+use log::{error, info};
+use rand::SeedableRng;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use sparrow::config::DEFAULT_SPARROW_CONFIG;
+use sparrow::consts::{DEFAULT_VALIDATION_TOLERANCE, LOG_LEVEL_FILTER_DEBUG, LOG_LEVEL_FILTER_RELEASE};
+use sparrow::optimizer::optimize;
+use sparrow::optimizer::separator::ContainerMode;
+use sparrow::util::ctrlc_terminator::CtrlCTerminator;
+use sparrow::util::early_terminator::EarlyTerminator;
+use sparrow::util::event_stream::EventStreamListener;
+use sparrow::util::io;
+use sparrow::util::io::{MainCli, SPOutput};
+use sparrow::util::live_server::LiveServer;
+use sparrow::util::replay::ReplayRecorder;
+use sparrow::util::rounding::round_output;
+use sparrow::util::run_controller::RunController;
+use sparrow::util::svg_exporter::SvgExporter;
+use sparrow::{EPOCH, EXPORT_LIVE_SVG, EXPORT_ONLY_FINAL_SVG};
+use crate::cli::{apply_early_termination, apply_search_options, apply_time_limits, early_termination_config, heatmap_options, initial_square_side, item_rotation_configs, set_container_mode, OUTPUT_DIR};
+
+#[derive(Args)]
+pub struct SolveArgs {
+    #[command(flatten)]
+    pub main_args: MainCli,
+
+    /// Packs the items into the smallest square instead of the shortest strip, the strip height of the input is ignored
+    #[arg(long, help = "Pack the items into a square instead of a strip")]
+    pub square: bool,
+
+    #[arg(short = 'o', long, default_value = OUTPUT_DIR, help = "Directory to write the solution, log and intermediate solutions to")]
+    pub output: String,
+}
+
+pub fn run(args: SolveArgs) -> Result<()> {
+    let output_dir = Path::new(&args.output);
+    fs::create_dir_all(output_dir)?;
+    //keep stdout clean if the event stream is written to it
+    let log_to_stderr = args.main_args.events.as_deref() == Some("-");
+    match cfg!(debug_assertions) {
+        true => io::init_logger(LOG_LEVEL_FILTER_DEBUG, &output_dir.join("log.txt"), log_to_stderr)?,
+        false => io::init_logger(LOG_LEVEL_FILTER_RELEASE, &output_dir.join("log.txt"), log_to_stderr)?,
+    }
+
+    let mut config = DEFAULT_SPARROW_CONFIG;
+    config.rng_seed = args.main_args.rng_seed.map(|s| s as usize);
+    apply_time_limits(&mut config, &args.main_args);
+    apply_early_termination(&mut config, &args.main_args);
+    apply_search_options(&mut config, &args.main_args)?;
+    let container = match args.square {
+        true => ContainerMode::Square,
+        false => ContainerMode::Strip,
+    };
+    set_container_mode(&mut config, container);
+
+    info!("[MAIN] container: {container:?}, explore: {:.3}s, compress: {:.3}s", config.expl_cfg.time_limit.as_secs_f64(), config.cmpr_cfg.time_limit.as_secs_f64());
+
+    let mut ext_instance = io::read_spp_instance_json(Path::new(&args.main_args.input))?;
+    let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+    let mut instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+
+    if container == ContainerMode::Square {
+        ext_instance.strip_height = initial_square_side(&instance);
+        instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+        info!("[MAIN] initial square side: {:.3}", ext_instance.strip_height);
+    }
+
+    let rng = match config.rng_seed {
+        Some(seed) => {
+            info!("[MAIN] using seed: {seed}");
+            Xoshiro256PlusPlus::seed_from_u64(seed as u64)
+        }
+        None => {
+            let seed = rand::random();
+            info!("[MAIN] no seed provided, using: {seed}");
+            Xoshiro256PlusPlus::seed_from_u64(seed)
+        }
+    };
+
+    let name = &ext_instance.name;
+    let final_svg_path = Some(format!("{}/final_{name}.svg", args.output));
+    let intermediate_svg_dir = match EXPORT_ONLY_FINAL_SVG {
+        true => None,
+        false => Some(format!("{}/sols_{name}", args.output)),
+    };
+    let live_svg_path = match EXPORT_LIVE_SVG {
+        true => Some("data/live/.live_solution.svg".to_string()),
+        false => None,
+    };
+
+    let controller = RunController::new(CtrlCTerminator::new(), args.main_args.control.as_ref().map(PathBuf::from))?;
+    let mut terminator = EarlyTerminator::new(controller, early_termination_config(&args.main_args));
+    let live_server = args.main_args.live_port.map(LiveServer::start).transpose()?;
+
+    let exporter = SvgExporter::new(final_svg_path, intermediate_svg_dir, live_svg_path, heatmap_options(&args.main_args));
+    let event_stream = args.main_args.events.as_deref().map(EventStreamListener::open).transpose()?;
+    let live_view = live_server.as_ref().map(|s| s.listener(name.clone()));
+    let recorder = args.main_args.record.then(|| ReplayRecorder::new(format!("{}/recording_{name}.json", args.output), name));
+    let mut sol_listener = (exporter, (event_stream, (live_view, (recorder, (terminator.listener(), terminator.inner.listener(output_dir))))));
+
+    let item_rot_configs = item_rotation_configs(&config, &args.main_args, instance.items.len())?;
+    let result = optimize(instance.clone(), rng, &mut sol_listener, &mut terminator, &config.expl_cfg, &config.cmpr_cfg, &item_rot_configs);
+    let solution = result.solution;
+
+    let mut final_ext_instance = ext_instance.clone();
+    if container == ContainerMode::Square {
+        final_ext_instance.strip_height = solution.strip_width();
+    }
+    let mut output = SPOutput {
+        instance: final_ext_instance,
+        solution: jagua_rs::probs::spp::io::export(&instance, &solution, *EPOCH),
+        rotation_histograms: result.rot_model.learned_histograms(),
+    };
+    if let Some(decimals) = args.main_args.decimals {
+        //if the rounding fails, the unrounded solution is written instead
+        match round_output(&output, container, decimals, DEFAULT_VALIDATION_TOLERANCE) {
+            Ok((rounded, report)) => {
+                info!("[MAIN] {report}");
+                output = rounded;
+            }
+            Err(e) => error!("[MAIN] rounding failed, writing the unrounded solution: {e}"),
+        }
+    }
+    io::write_json(&output, &output_dir.join(format!("final_{name}.json")), log::Level::Info)?;
+
+    info!("[MAIN] final size: {:.3}, density: {:.3}%", solution.strip_width(), solution.density(&instance) * 100.0);
+    Ok(())
+}
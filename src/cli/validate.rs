@@ -0,0 +1,66 @@
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use itertools::Itertools;
+use sparrow::consts::DEFAULT_VALIDATION_TOLERANCE;
+use sparrow::util::io;
+use sparrow::util::validator::{validate, ValidationReport};
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[arg(short = 'i', long, help = "Path to the solution file: JSON (as written by solve) or CSV (as written by batch)")]
+    pub input: String,
+
+    /// A CSV file contains no instance, nor the demand of its items: the demand of every item in a CSV solution is set to its number of placed copies
+    #[arg(long, help = "Path to the instance JSON file (required for CSV solutions)")]
+    pub instance: Option<String>,
+
+    #[arg(long, default_value_t = DEFAULT_VALIDATION_TOLERANCE, help = "Ignore overlaps up to this fraction of the area of an item")]
+    pub tolerance: f64,
+}
+
+pub fn run(args: ValidateArgs) -> Result<()> {
+    let input = Path::new(&args.input);
+    let reports = match input.extension().and_then(|e| e.to_str()) {
+        Some("csv") => validate_csv(input, &args)?,
+        _ => {
+            let output = io::read_spp_output_json(input)?;
+            let container = (output.solution.strip_width, output.instance.strip_height);
+            let report = validate(&output.instance, &output.solution.layout.placed_items, Some(container), args.tolerance)?;
+            vec![(output.instance.name.clone(), report)]
+        }
+    };
+
+    let mut n_infeasible = 0;
+    for (name, report) in &reports {
+        println!("[VALIDATE] {name}: {report}");
+        if !report.is_feasible() {
+            n_infeasible += 1;
+        }
+    }
+    match n_infeasible {
+        0 => Ok(()),
+        n => bail!("{n} of {} solutions are infeasible", reports.len()),
+    }
+}
+
+/// Validates every solution in a CSV file, on the instance with the demand of every placed item set to its number of placed copies
+fn validate_csv(path: &Path, args: &ValidateArgs) -> Result<Vec<(String, ValidationReport)>> {
+    let instance_path = args.instance.as_ref().context("validating a CSV solution requires the instance (--instance)")?;
+    let ext_instance = io::read_spp_instance_json(Path::new(instance_path))?;
+
+    let mut reports = vec![];
+    for (n_items, placed_items) in io::read_csv(path)? {
+        let placed_qtys = placed_items.iter().counts_by(|pi| pi.item_id);
+        let mut instance = ext_instance.clone();
+        for item in instance.items.iter_mut() {
+            if let Some(&qty) = placed_qtys.get(&item.base.id) {
+                item.demand = qty as u64;
+            }
+        }
+        //the CSV does not define the container, only overlap is checked
+        let report = validate(&instance, &placed_items, None, args.tolerance)?;
+        reports.push((format!("{:0>3}", n_items), report));
+    }
+    Ok(reports)
+}
@@ -1,5 +1,10 @@
-use crate::optimizer::separator::SeparatorConfig;
-use crate::sample::search::SampleConfig;
+use crate::optimizer::polish::PolishConfig;
+use crate::optimizer::separator::{AcceptanceCriterion, ContainerMode, SeparatorConfig};
+use crate::optimizer::split::{SplitConfig, SplitScore};
+use crate::consts::{DEFAULT_ROTATION_CONFIG, DEFAULT_WEIGHT_CONFIG};
+use crate::sample::refiner::RefineStrategy;
+use crate::sample::sampler::ContainerSamplerMix;
+use crate::sample::search::{FocussedSampler, SampleConfig};
 use jagua_rs::collision_detection::CDEConfig;
 use jagua_rs::geometry::fail_fast::SPSurrogateConfig;
 use std::time::Duration;
@@ -42,6 +47,10 @@ pub struct CompressionConfig {
     pub time_limit: Duration,
     pub shrink_decay: ShrinkDecayStrategy,
     pub separator_config: SeparatorConfig,
+    /// Gradient-based polishing of all items jointly, attempted whenever the separator fails to compress
+    pub polish: Option<PolishConfig>,
+    /// Selection of the positions at which the container is split when shrinking
+    pub split_config: SplitConfig,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -68,7 +77,18 @@ pub const DEFAULT_SPARROW_CONFIG: SparrowConfig = SparrowConfig {
                 n_container_samples: 50,
                 n_focussed_samples: 25,
                 n_coord_descents: 3,
+                rotation: DEFAULT_ROTATION_CONFIG,
+                container_sampler_mix: ContainerSamplerMix::UNIFORM,
+                focussed_sampler: FocussedSampler::UniformBBox,
+                pre_refiner: RefineStrategy::CoordinateDescent,
+                final_refiner: RefineStrategy::CoordinateDescent,
+                adaptive_budget: None,
             },
+            acceptance: AcceptanceCriterion::Greedy,
+            swap_ratio: 0.0,
+            ruin_recreate: None,
+            weights: DEFAULT_WEIGHT_CONFIG,
+            container: ContainerMode::Square,
         },
         large_item_ch_area_cutoff_percentile: 0.75
     },
@@ -85,7 +105,24 @@ pub const DEFAULT_SPARROW_CONFIG: SparrowConfig = SparrowConfig {
                 n_container_samples: 50,
                 n_focussed_samples: 25,
                 n_coord_descents: 3,
+                rotation: DEFAULT_ROTATION_CONFIG,
+                container_sampler_mix: ContainerSamplerMix::UNIFORM,
+                focussed_sampler: FocussedSampler::UniformBBox,
+                pre_refiner: RefineStrategy::CoordinateDescent,
+                final_refiner: RefineStrategy::CoordinateDescent,
+                adaptive_budget: None,
             },
+            acceptance: AcceptanceCriterion::Greedy,
+            swap_ratio: 0.0,
+            ruin_recreate: None,
+            weights: DEFAULT_WEIGHT_CONFIG,
+            container: ContainerMode::Square,
+        },
+        polish: None,
+        split_config: SplitConfig {
+            n_candidates: 16,
+            score: SplitScore::ItemArea,
+            selection_stddev: 0.25,
         },
     },
     cde_config: CDEConfig {
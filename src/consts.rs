@@ -1,9 +1,31 @@
 use jagua_rs::io::svg::{SvgDrawOptions, SvgLayoutTheme};
-use crate::sample::search::SampleConfig;
+use crate::optimizer::polish::PolishConfig;
+use crate::optimizer::ruin_recreate::{RuinRecreateConfig, RuinRegion};
+use crate::quantify::tracker::{WeightConfig, WeightSchedule, WeightSelection};
+use crate::sample::budget::AdaptiveBudgetConfig;
+use crate::sample::refiner::RefineStrategy;
+use crate::sample::rotation::{RotationConfig, RotationLearningConfig};
+use crate::sample::sampler::ContainerSamplerMix;
+use crate::sample::search::{FocussedSampler, SampleConfig};
+use std::time::Duration;
 
 pub const GLS_WEIGHT_MAX_INC_RATIO: f64 = 2.0;
 pub const GLS_WEIGHT_MIN_INC_RATIO: f64 = 1.2;
 pub const GLS_WEIGHT_DECAY: f64 = 0.95;
+
+/// Weight dynamics of the paper: all colliding pairs and the container share one schedule, weights never drop below 1.0
+pub const DEFAULT_WEIGHT_CONFIG: WeightConfig = WeightConfig {
+    pair: WeightSchedule {
+        max_inc_ratio: GLS_WEIGHT_MAX_INC_RATIO,
+        min_inc_ratio: GLS_WEIGHT_MIN_INC_RATIO,
+        decay: GLS_WEIGHT_DECAY,
+    },
+    container: None,
+    selection: WeightSelection::AllColliding,
+    min_weight: 1.0,
+    max_weight: None,
+};
+
 pub const OVERLAP_PROXY_EPSILON_DIAM_RATIO: f64 = 0.01;
 
 
@@ -25,9 +47,42 @@ pub const SND_REFINE_CD_TL_RATIOS: (f64, f64) = (0.01, 0.001);
 /// Step sizes for rotation in the second (final) refinement
 pub const SND_REFINE_CD_R_STEPS: (f64, f64) = (f64::to_radians(0.5), f64::to_radians(0.05));
 
+/// Maximum number of iterations of the Nelder-Mead refinement
+pub const NM_MAX_ITERATIONS: usize = 200;
+
+/// Rotation sampling used unless configured otherwise: 16 evenly spaced rotations for continuous rotation, without learning
+pub const DEFAULT_ROTATION_CONFIG: RotationConfig = RotationConfig {
+    n_samples: 16,
+    pre_refine_steps: PRE_REFINE_CD_R_STEPS,
+    final_refine_steps: SND_REFINE_CD_R_STEPS,
+    learning: None,
+};
+
+/// Rotation learning enabled with `--learn-rotations`: bins of 10°, a quarter of the rotations is still sampled uniformly
+pub const DEFAULT_ROTATION_LEARNING_CONFIG: RotationLearningConfig = RotationLearningConfig {
+    n_bins: 36,
+    uniform_ratio: 0.25,
+};
+
+/// Focussed sampler mirroring the `LSSampler` defaults of the lbf crate:
+/// translation stddev decays from 1% to 0.05% of the container's largest dimension, rotation stddev from 2° to 0.5°
+pub const LS_FOCUSSED_SAMPLER: FocussedSampler = FocussedSampler::LocalSearch {
+    sd_transl: (0.01, 0.0005),
+    sd_rot: (f64::to_radians(2.0), f64::to_radians(0.5)),
+};
+
 /// If two samples are closer than this ratio of the item's min dimension, they are considered duplicates
 pub const UNIQUE_SAMPLE_THRESHOLD: f64 = 0.05;
 
+/// Maximum ratio between the areas of two items to be considered for a swap
+pub const SWAP_MAX_AREA_RATIO: f64 = 1.5;
+
+/// Initial temperature of the annealing acceptance criterion, at which a 10% increase in weighted loss of an item is accepted with a probability of 1/e
+pub const ANNEALING_T_INIT: f64 = 0.1;
+
+/// Multiplier of the temperature of the annealing acceptance criterion after every iteration of the separator
+pub const ANNEALING_T_DECAY: f64 = 0.99;
+
 pub const DEFAULT_EXPLORE_TIME_RATIO: f64 = 0.8;
 pub const DEFAULT_COMPRESS_TIME_RATIO: f64 = 0.2;
 
@@ -35,6 +90,21 @@ pub const DEFAULT_MAX_CONSEQ_FAILS_EXPL: usize = 10;
 
 pub const DEFAULT_FAIL_DECAY_RATIO_CMPR: f64 = 0.9;
 
+/// Time limit of the exploration phase of every job of a batch, unless set by the arguments
+pub const BATCH_EXPLORE_TIME_LIMIT: Duration = Duration::from_secs(240);
+
+/// Time limit of the compression phase of every job of a batch, unless set by the arguments
+pub const BATCH_COMPRESS_TIME_LIMIT: Duration = Duration::from_secs(120);
+
+/// Density of the initial square container when solving a square packing problem, before the exploration phase shrinks it
+pub const SQUARE_INIT_DENSITY: f64 = 0.5;
+
+/// Overlap and outside areas up to this fraction of the area of an item are ignored when validating a solution
+pub const DEFAULT_VALIDATION_TOLERANCE: f64 = 1e-9;
+
+/// Maximum number of steps to resolve the collisions caused by rounding an exported solution
+pub const MAX_ROUNDING_ITERATIONS: usize = 1000;
+
 pub const LOG_LEVEL_FILTER_RELEASE: log::LevelFilter = log::LevelFilter::Info;
 
 pub const LOG_LEVEL_FILTER_DEBUG: log::LevelFilter = log::LevelFilter::Debug;
@@ -52,4 +122,35 @@ pub const LBF_SAMPLE_CONFIG: SampleConfig = SampleConfig {
     n_container_samples: 1000,
     n_focussed_samples: 0,
     n_coord_descents: 3,
+    rotation: DEFAULT_ROTATION_CONFIG,
+    container_sampler_mix: ContainerSamplerMix::UNIFORM,
+    focussed_sampler: FocussedSampler::UniformBBox,
+    pre_refiner: RefineStrategy::CoordinateDescent,
+    final_refiner: RefineStrategy::CoordinateDescent,
+    adaptive_budget: None,
+};
+
+/// Ruin-and-recreate move enabled with `--ruin-recreate`: clears a disc as large as the largest item of one of the three
+/// highest-loss pairs and re-inserts the removed items as the LBF constructor would
+pub const DEFAULT_RUIN_RECREATE_CONFIG: RuinRecreateConfig = RuinRecreateConfig {
+    region: RuinRegion::Disc,
+    size_ratio: 1.0,
+    n_top_pairs: 3,
+    sample_config: LBF_SAMPLE_CONFIG,
+};
+
+/// Polishing enabled with `--polish`: at most 100 gradient descent steps, starting at a tenth of the average item diameter
+pub const DEFAULT_POLISH_CONFIG: PolishConfig = PolishConfig {
+    n_iterations: 100,
+    step_init: 0.1,
+    step_limit: 0.001,
+    container_weight: 10.0,
+};
+
+/// Adaptive sample budgets enabled with `--adaptive-budget`: every search weighs 5% in the statistics of its item,
+/// at least 10% of the samples goes to either type of sample and 1 to 6 coordinate descents are performed
+pub const DEFAULT_ADAPTIVE_BUDGET_CONFIG: AdaptiveBudgetConfig = AdaptiveBudgetConfig {
+    smoothing: 0.05,
+    min_share: 0.1,
+    n_coord_descents_range: (1, 6),
 };
\ No newline at end of file
@@ -13,6 +13,7 @@ pub struct SeparationEvaluator<'a> {
     item: &'a Item,
     collector: SpecializedHazardCollector<'a>,
     shape_buff: SPolygon,
+    excluded_dt: Option<DTransformation>,
     n_evals: usize,
 }
 
@@ -30,9 +31,15 @@ impl<'a> SeparationEvaluator<'a> {
             item,
             collector,
             shape_buff: item.shape_cd.as_ref().clone(),
+            excluded_dt: None,
             n_evals: 0,
         }
     }
+
+    /// Evaluates the given transformation as [`SampleEval::Invalid`], forcing a search to find a placement elsewhere.
+    pub fn exclude(&mut self, dt: DTransformation) {
+        self.excluded_dt = Some(dt);
+    }
 }
 
 impl<'a> SampleEvaluator for SeparationEvaluator<'a> {
@@ -40,6 +47,9 @@ impl<'a> SampleEvaluator for SeparationEvaluator<'a> {
     /// Algorithm 7 from https://doi.org/10.48550/arXiv.2509.13329
     fn evaluate_sample(&mut self, dt: DTransformation, upper_bound: Option<SampleEval>) -> SampleEval {
         self.n_evals += 1;
+        if self.excluded_dt == Some(dt) {
+            return SampleEval::Invalid;
+        }
         let cde = self.layout.cde();
 
         //samples which evaluate higher than this will certainly be rejected
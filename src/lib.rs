@@ -36,4 +36,7 @@ pub const EXPORT_ONLY_FINAL_SVG: bool = true;
 pub const EXPORT_ONLY_FINAL_SVG: bool = false;
 
 #[cfg(all(feature = "live_svg", feature = "only_final_svg"))]
-compile_error!("The features `live_svg` and `only_final_svg` are mutually exclusive.");
\ No newline at end of file
+compile_error!("The features `live_svg` and `only_final_svg` are mutually exclusive.");
+
+#[cfg(all(feature = "simd", feature = "exact_loss"))]
+compile_error!("The features `simd` and `exact_loss` are mutually exclusive.");
@@ -1,9 +1,10 @@
 use jagua_rs::Instant;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use log::info;
-use rand::Rng;
 use crate::config::{CompressionConfig, ShrinkDecayStrategy};
-use crate::optimizer::separator::Separator;
+use crate::optimizer::polish::{polish, PolishConfig};
+use crate::optimizer::separator::{ContainerMode, Separator};
+use crate::optimizer::split::{SplitAxis, SplitSelector};
 use crate::util::listener::{ReportType, SolutionListener};
 use crate::util::terminator::Terminator;
 
@@ -19,6 +20,7 @@ pub fn compression_phase(
     let mut best = init.clone();
     let start = Instant::now();
     let mut n_failed_attempts = 0;
+    let mut split_selector = SplitSelector::new(config.split_config);
 
     let shrink_step_size = |n_failed_attempts: i32| -> f64 {
         match config.shrink_decay {
@@ -34,7 +36,7 @@ pub fn compression_phase(
         }
     };
     while !term.kill() && let step = shrink_step_size(n_failed_attempts) && step >= config.shrink_range.1 {
-        match attempt_to_compress(sep, &best, step, term, sol_listener) {
+        match attempt_to_compress(sep, &best, step, &mut split_selector, term, sol_listener) {
             Some(compacted_sol) => {
                 info!("[CMPR] success at {:.3}% ({:.3} | {:.3}%)", step * 100.0, compacted_sol.strip_width(), compacted_sol.density(instance) * 100.0);
                 sol_listener.report(ReportType::CmprFeas, &compacted_sol, instance);
@@ -42,29 +44,63 @@ pub fn compression_phase(
             }
             None => {
                 info!("[CMPR] failed at {:.3}%", step * 100.0);
-                n_failed_attempts += 1;
+                let polished_sol = config.polish
+                    .and_then(|polish_config| attempt_to_polish(sep, &best, step, &polish_config, term, sol_listener));
+                match polished_sol {
+                    Some(polished_sol) => {
+                        info!("[CMPR] polishing success at {:.3}% ({:.3} | {:.3}%)", step * 100.0, polished_sol.strip_width(), polished_sol.density(instance) * 100.0);
+                        sol_listener.report(ReportType::CmprFeas, &polished_sol, instance);
+                        best = polished_sol;
+                    }
+                    None => n_failed_attempts += 1,
+                }
             }
         }
     }
     info!("[CMPR] finished, compressed from {:.3}% to {:.3}% (+{:.3}%)", init.density(instance) * 100.0, best.density(instance) * 100.0, (best.density(instance) - init.density(instance)) * 100.0);
+    info!("[CMPR] split success rate: {:.1}% ({}/{})", split_selector.success_rate() * 100.0, split_selector.n_successes, split_selector.n_attempts);
     best
 }
 
 
-fn attempt_to_compress(sep: &mut Separator, init: &SPSolution, r_shrink: f64, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Option<SPSolution> {
+fn attempt_to_compress(sep: &mut Separator, init: &SPSolution, r_shrink: f64, split_selector: &mut SplitSelector, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Option<SPSolution> {
     //restore to the initial solution and width
-    sep.change_square_size(init.strip_width(), None);
+    sep.change_size(init.strip_width(), None);
     sep.rollback(&init, None);
 
-    //shrink the container at a random position
+    //shrink the container at lines which cut through little of the layout (in both dimensions of a square)
     let new_size = init.strip_width() * (1.0 - r_shrink);
-    let split_pos = sep.rng.random_range(0.0..sep.prob.strip_width());
-    sep.change_square_size(new_size, Some(split_pos));
+    //without a selected split, the container is split at its center
+    let center = init.strip_width() / 2.0;
+    let split_x = split_selector.select(&sep.prob.layout, SplitAxis::Vertical, &mut sep.rng).unwrap_or(center);
+    let split_y = match sep.config.container {
+        ContainerMode::Strip => f64::INFINITY,
+        ContainerMode::Square => split_selector.select(&sep.prob.layout, SplitAxis::Horizontal, &mut sep.rng).unwrap_or(center),
+    };
+    sep.change_size(new_size, Some((split_x, split_y)));
 
     //try to separate layout, if all collisions are eliminated, return the solution
     let (compacted_sol, ot) = sep.separate(term, sol_listener);
-    match ot.get_total_loss() == 0.0 {
+    let success = ot.get_total_loss() == 0.0;
+    split_selector.report(success);
+    match success {
         true => Some(compacted_sol),
         false => None,
     }
-}
\ No newline at end of file
+}
+
+/// Shrinks the container while moving all items jointly (see [`polish`]) and separates the remaining collisions
+pub fn attempt_to_polish(sep: &mut Separator, init: &SPSolution, r_shrink: f64, config: &PolishConfig, term: &impl Terminator, sol_listener: &mut impl SolutionListener) -> Option<SPSolution> {
+    //restore to the initial solution and width
+    sep.change_size(init.strip_width(), None);
+    sep.rollback(init, None);
+
+    let new_size = init.strip_width() * (1.0 - r_shrink);
+    polish(sep, new_size, config);
+
+    let (polished_sol, ot) = sep.separate(term, sol_listener);
+    match ot.get_total_loss() == 0.0 {
+        true => Some(polished_sol),
+        false => None,
+    }
+}
@@ -13,6 +13,7 @@ use slotmap::SecondaryMap;
 use crate::config::ExplorationConfig;
 use crate::FMT;
 use crate::optimizer::separator::{Separator, SeparatorConfig};
+use crate::quantify::total_overlap_area;
 use crate::sample::uniform_sampler::convert_sample_to_closest_feasible;
 use crate::util::listener::{ReportType, SolutionListener};
 use crate::util::terminator::Terminator;
@@ -41,16 +42,14 @@ pub fn exploration_phase(instance: &SPInstance, sep: &mut Separator, sol_listene
                 feasible_solutions.push(local_best.0.clone());
                 sol_listener.report(ReportType::ExplFeas, &local_best.0, instance);
             }
-            // THAY ĐỔI Ở ĐÂY: Thu nhỏ cả hình vuông
             let next_size = current_size * (1.0 - config.shrink_step);
-            info!("[EXPL] shrinking square by {}%: {:.3} -> {:.3}", config.shrink_step * 100.0, current_size, next_size);
-            
-            // Dùng hàm mới change_square_size
-            sep.change_square_size(next_size, None);
+            info!("[EXPL] shrinking container by {}%: {:.3} -> {:.3}", config.shrink_step * 100.0, current_size, next_size);
+            sep.change_size(next_size, None);
             current_size = next_size;
             solution_pool.clear();
         } else {
-            info!("[EXPL] unable to reach feasibility (size: {:.3}, ...)", current_size);
+            info!("[EXPL] unable to reach feasibility (size: {:.3}, overlap area: {})", current_size, FMT().fmt2(total_overlap_area(&local_best.0.layout_snapshot)));
+            sol_listener.report_tracker(&local_best.1);
             sol_listener.report(ReportType::ExplInfeas, &local_best.0, instance);
 
             //layout was not successfully separated, add to local bests
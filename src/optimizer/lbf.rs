@@ -1,5 +1,6 @@
 use crate::eval::lbf_evaluator::LBFEvaluator;
 use crate::eval::sample_eval::SampleEval;
+use crate::sample::rotation::RotationModel;
 use crate::sample::search::{search_placement, SampleConfig};
 use itertools::Itertools;
 use log::debug;
@@ -17,6 +18,7 @@ pub struct LBFBuilder {
     pub prob: SPProblem,
     pub rng: Xoshiro256PlusPlus,
     pub sample_config: SampleConfig,
+    pub rot_model: RotationModel,
 }
 
 impl LBFBuilder {
@@ -26,12 +28,14 @@ impl LBFBuilder {
         sample_config: SampleConfig,
     ) -> Self {
         let prob = SPProblem::new(instance.clone());
+        let rot_model = RotationModel::new(instance.items.len(), sample_config.rotation);
 
         Self {
             instance,
             prob,
             rng,
             sample_config,
+            rot_model,
         }
     }
 
@@ -83,11 +87,11 @@ impl LBFBuilder {
         let item = self.instance.item(item_id);
         let evaluator = LBFEvaluator::new(layout, item);
 
-        let (best_sample, _) = search_placement(layout, item, None, evaluator, self.sample_config, &mut self.rng);
+        let (best_sample, _) = search_placement(layout, item, None, evaluator, self.sample_config, &self.rot_model, &mut self.rng);
 
         match best_sample {
             Some((d_transf, SampleEval::Clear { .. })) => {
-                Some(SPPlacement { item_id, d_transf })
+                Some(SPPlacement { item_id, copy_idx: self.prob.next_copy_idx(item_id), d_transf })
             }
             _ => None
         }
@@ -1,6 +1,8 @@
 use crate::config::*;
 use crate::optimizer::lbf::LBFBuilder;
-use crate::optimizer::separator::Separator;
+use crate::optimizer::separator::{ContainerMode, Separator};
+use jagua_rs::entities::Instance;
+use jagua_rs::geometry::geo_enums::RotationRange;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use rand::{RngCore, SeedableRng};
 use std::time::Duration;
@@ -8,22 +10,46 @@ use rand_xoshiro::Xoshiro256PlusPlus;
 use crate::consts::LBF_SAMPLE_CONFIG;
 use crate::optimizer::compress::compression_phase;
 use crate::optimizer::explore::exploration_phase;
+use crate::sample::budget::SampleBudgets;
+use crate::sample::rotation::{RotationConfig, RotationModel};
 use crate::util::listener::{ReportType, SolutionListener};
 use crate::util::terminator::Terminator;
+use crate::quantify::total_overlap_area;
+use crate::FMT;
+use log::info;
 
 pub mod lbf;
 pub mod separator;
-mod worker;
+pub mod worker;
 pub mod explore;
 pub mod compress;
+pub mod polish;
+pub mod split;
+pub mod ruin_recreate;
+
+/// Outcome of [`optimize`]: the final solution, together with what was learned during the run
+pub struct OptimizationResult {
+    pub solution: SPSolution,
+    /// Final solution of the exploration phase, from which the compression phase started
+    pub explore_solution: SPSolution,
+    /// Rotation sampling of every item, including the distributions learned during both phases
+    pub rot_model: RotationModel,
+    /// Sample budgets of every item, as learned during both phases
+    pub budgets: SampleBudgets,
+}
 
 ///Algorithm 11 from https://doi.org/10.48550/arXiv.2509.13329
-pub fn optimize(instance: SPInstance, mut rng: Xoshiro256PlusPlus, sol_listener: &mut impl SolutionListener, terminator: &mut impl Terminator, expl_config: &ExplorationConfig, cmpr_config: &CompressionConfig) -> SPSolution {
+/// `item_rot_configs` override the rotation sampling of single items in both phases.
+pub fn optimize(instance: SPInstance, mut rng: Xoshiro256PlusPlus, sol_listener: &mut impl SolutionListener, terminator: &mut impl Terminator, expl_config: &ExplorationConfig, cmpr_config: &CompressionConfig, item_rot_configs: &[(usize, RotationConfig)]) -> OptimizationResult {
     let mut next_rng = || Xoshiro256PlusPlus::seed_from_u64(rng.next_u64());
     let builder = LBFBuilder::new(instance.clone(), next_rng(), LBF_SAMPLE_CONFIG).construct();
 
     terminator.new_timeout(expl_config.time_limit);
     let mut expl_separator = Separator::new(builder.instance, builder.prob, next_rng(), expl_config.separator_config);
+    square_up(&mut expl_separator);
+    for &(item_id, rot_config) in item_rot_configs {
+        expl_separator.rot_model.set_item_config(item_id, rot_config);
+    }
     let solutions = exploration_phase(
         &instance,
         &mut expl_separator,
@@ -32,9 +58,15 @@ pub fn optimize(instance: SPInstance, mut rng: Xoshiro256PlusPlus, sol_listener:
         expl_config,
     );
     let final_explore_sol = solutions.last().unwrap().clone();
+    if let Some(reason) = terminator.kill_reason() {
+        info!("[OPT] exploration phase stopped: {reason}");
+    }
 
     terminator.new_timeout(cmpr_config.time_limit);
     let mut cmpr_separator = Separator::new(expl_separator.instance, expl_separator.prob, next_rng(), cmpr_config.separator_config);
+    //continue with the rotation configs of single items, and the rotations and sample budgets learned during exploration
+    cmpr_separator.rot_model.adopt(&expl_separator.rot_model);
+    cmpr_separator.budgets.adopt_stats(&expl_separator.budgets);
     let cmpr_sol = compression_phase(
         &instance,
         &mut cmpr_separator,
@@ -43,8 +75,37 @@ pub fn optimize(instance: SPInstance, mut rng: Xoshiro256PlusPlus, sol_listener:
         terminator,
         cmpr_config,
     );
+    if let Some(reason) = terminator.kill_reason() {
+        info!("[OPT] compression phase stopped: {reason}");
+    }
+
+    info!("[OPT] final solution, exact overlap area between items: {}", FMT().fmt2(total_overlap_area(&cmpr_sol.layout_snapshot)));
+
+    for item in instance.items().filter(|i| i.allowed_rotation == RotationRange::Continuous) {
+        if let Some(histogram) = cmpr_separator.rot_model.format_histogram(item.id) {
+            info!("[OPT] learned rotation distribution of item {}: {}", item.id, histogram);
+        }
+    }
+    for item in instance.items() {
+        if let Some(budget) = cmpr_separator.budgets.format_budget(item.id) {
+            info!("[OPT] learned sample budget of item {}: {}", item.id, budget);
+        }
+    }
 
     sol_listener.report(ReportType::Final, &cmpr_sol, &instance);
 
-    cmpr_sol
-}
\ No newline at end of file
+    OptimizationResult {
+        solution: cmpr_sol,
+        explore_solution: final_explore_sol,
+        rot_model: cmpr_separator.rot_model,
+        budgets: cmpr_separator.budgets,
+    }
+}
+
+/// Grows the container of a constructed solution into a square which contains all items, if the separator packs into a square
+pub fn square_up(sep: &mut Separator) {
+    if sep.config.container == ContainerMode::Square {
+        let bbox = sep.prob.layout.container.outer_cd.bbox;
+        sep.change_size(f64::max(bbox.width(), bbox.height()), Some((f64::INFINITY, f64::INFINITY)));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::consts::OVERLAP_PROXY_EPSILON_DIAM_RATIO;
+use crate::optimizer::separator::{ContainerMode, Separator};
+use crate::FMT;
+use itertools::Itertools;
+use jagua_rs::entities::{Instance, PItemKey};
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::geometry::primitives::{Circle, Rect};
+use jagua_rs::geometry::DTransformation;
+use log::debug;
+use std::f64::consts::PI;
+
+/// Configuration of the gradient-based polishing stage of the compression phase
+#[derive(Debug, Clone, Copy)]
+pub struct PolishConfig {
+    /// Maximum number of gradient descent iterations per attempt
+    pub n_iterations: usize,
+    /// Initial step size (maximum displacement of any item), relative to the average item diameter
+    pub step_init: f64,
+    /// Step size below which the descent is stopped, relative to the average item diameter
+    pub step_limit: f64,
+    /// Weight of the poles sticking out of the container, relative to the overlap between items
+    pub container_weight: f64,
+}
+
+/// Shrinks the container to `new_size` by uniformly scaling the positions of all items towards its origin
+/// and then moves all items simultaneously to minimize the pole-based overlap proxy (Algorithm 3 from https://doi.org/10.48550/arXiv.2509.13329).
+/// The proxy is treated as a differentiable function of all item positions and rotations, minimized with a gradient descent.
+/// The resulting layout is loaded into the separator, but is not guaranteed to be feasible.
+pub fn polish(sep: &mut Separator, new_size: f64, config: &PolishConfig) {
+    let old_bbox = sep.prob.layout.container.outer_cd.bbox;
+    let scale_x = new_size / sep.prob.strip_width();
+    //the height of a strip is fixed
+    let scale_y = match sep.config.container {
+        ContainerMode::Strip => 1.0,
+        ContainerMode::Square => scale_x,
+    };
+
+    let mut state = PolishState::new(sep, config);
+
+    //scale the positions of all items (their centroids) towards the origin of the container
+    for (pi, x) in state.items.iter().zip(state.x.iter_mut()) {
+        let c = sep.prob.layout.placed_items[pi.pk].shape.centroid();
+        x[0] += (c.0 - old_bbox.x_min) * (scale_x - 1.0);
+        x[1] += (c.1 - old_bbox.y_min) * (scale_y - 1.0);
+    }
+    state.container = Rect::try_new(old_bbox.x_min, old_bbox.y_min, old_bbox.x_min + old_bbox.width() * scale_x, old_bbox.y_min + old_bbox.height() * scale_y).unwrap();
+
+    let (init_loss, n_iter) = state.descend(config);
+    debug!("[POL] polished in {} iterations, proxy: {} -> {}", n_iter, FMT().fmt2(init_loss), FMT().fmt2(state.evaluate(&state.x, None)));
+
+    //apply the new positions to the separator, and only then shrink the container (no items have to be shifted anymore)
+    for (pi, x) in state.items.iter().zip(state.x.iter()) {
+        let d_transf = DTransformation::new(x[2] / pi.rot_scale, (x[0], x[1]));
+        sep.move_item(pi.pk, d_transf);
+    }
+    sep.change_size(new_size, Some((f64::INFINITY, f64::INFINITY)));
+}
+
+struct PolishItem {
+    pk: PItemKey,
+    /// Poles of the item in its reference orientation
+    poles: Vec<Circle>,
+    /// Distance from the reference origin to the farthest point of any pole
+    reach: f64,
+    diameter: f64,
+    /// Radius used to express the rotation as an arc length, which makes its gradient comparable to the translations
+    rot_scale: f64,
+    rotatable: bool,
+}
+
+struct PolishState {
+    items: Vec<PolishItem>,
+    /// Per item: translation in x, translation in y and rotation (as arc length)
+    x: Vec<[f64; 3]>,
+    container: Rect,
+    container_weight: f64,
+    avg_diameter: f64,
+}
+
+impl PolishState {
+    fn new(sep: &Separator, config: &PolishConfig) -> Self {
+        let (items, x): (Vec<_>, Vec<_>) = sep.prob.layout.placed_items.iter()
+            .map(|(pk, pi)| {
+                let item = sep.instance.item(pi.item_id);
+                let poles = item.shape_cd.surrogate().poles.clone();
+                let reach = poles.iter()
+                    .map(|p| f64::hypot(p.center.0, p.center.1) + p.radius)
+                    .fold(0.0, f64::max);
+                let rot_scale = item.shape_cd.diameter / 2.0;
+                let rotatable = item.allowed_rotation == RotationRange::Continuous;
+                let (tx, ty) = pi.d_transf.translation();
+                let polish_item = PolishItem { pk, poles, reach, diameter: item.shape_cd.diameter, rot_scale, rotatable };
+                (polish_item, [tx, ty, pi.d_transf.rotation() * rot_scale])
+            })
+            .unzip();
+
+        let avg_diameter = items.iter().map(|i| i.diameter).sum::<f64>() / items.len() as f64;
+
+        Self {
+            items,
+            x,
+            container: sep.prob.layout.container.outer_cd.bbox,
+            container_weight: config.container_weight,
+            avg_diameter,
+        }
+    }
+
+    /// Gradient descent with an adaptive step size, returns the initial value of the proxy and the number of iterations
+    fn descend(&mut self, config: &PolishConfig) -> (f64, usize) {
+        let mut grad = vec![[0.0; 3]; self.x.len()];
+        let mut step = config.step_init * self.avg_diameter;
+        let step_limit = config.step_limit * self.avg_diameter;
+
+        let init_loss = self.evaluate(&self.x, Some(&mut grad));
+        let mut loss = init_loss;
+        let mut n_iter = 0;
+
+        while n_iter < config.n_iterations && step > step_limit {
+            n_iter += 1;
+            //normalize the gradient, so that the step size is the largest displacement of any item
+            let max_grad = grad.iter().flatten().fold(0.0, |a: f64, g| a.max(g.abs()));
+            if max_grad == 0.0 {
+                break;
+            }
+            let candidate = self.x.iter().zip(grad.iter())
+                .map(|(x, g)| [0, 1, 2].map(|i| x[i] - step * g[i] / max_grad))
+                .collect_vec();
+
+            let mut candidate_grad = vec![[0.0; 3]; self.x.len()];
+            let candidate_loss = self.evaluate(&candidate, Some(&mut candidate_grad));
+            if candidate_loss < loss {
+                (self.x, grad, loss) = (candidate, candidate_grad, candidate_loss);
+                step *= 1.2;
+            } else {
+                step *= 0.5;
+            }
+        }
+        (init_loss, n_iter)
+    }
+
+    /// Evaluates the pole-based overlap proxy of the entire layout for the given positions, optionally computing its gradient
+    fn evaluate(&self, x: &[[f64; 3]], mut grad: Option<&mut Vec<[f64; 3]>>) -> f64 {
+        if let Some(grad) = grad.as_mut() {
+            grad.iter_mut().for_each(|g| *g = [0.0; 3]);
+        }
+
+        //transform all poles, together with the derivative of their centers to the rotation
+        let transformed = self.items.iter().zip(x.iter())
+            .map(|(pi, x)| {
+                let (sin, cos) = (x[2] / pi.rot_scale).sin_cos();
+                pi.poles.iter().map(|p| {
+                    let (rx, ry) = (cos * p.center.0 - sin * p.center.1, sin * p.center.0 + cos * p.center.1);
+                    let d_rot = match pi.rotatable {
+                        true => (-ry / pi.rot_scale, rx / pi.rot_scale),
+                        false => (0.0, 0.0),
+                    };
+                    ((rx + x[0], ry + x[1]), p.radius, d_rot)
+                }).collect_vec()
+            })
+            .collect_vec();
+
+        let mut total = 0.0;
+
+        //overlap between pairs of items
+        for (i, j) in (0..self.items.len()).tuple_combinations() {
+            let (pi, pj) = (&self.items[i], &self.items[j]);
+            let epsilon = f64::max(pi.diameter, pj.diameter) * OVERLAP_PROXY_EPSILON_DIAM_RATIO;
+            if f64::hypot(x[i][0] - x[j][0], x[i][1] - x[j][1]) > pi.reach + pj.reach + epsilon {
+                continue;
+            }
+            for &((x1, y1), r1, dr1) in transformed[i].iter() {
+                for &((x2, y2), r2, dr2) in transformed[j].iter() {
+                    let d = f64::hypot(x1 - x2, y1 - y2);
+                    let pd = (r1 + r2) - d;
+                    //same decay as the overlap proxy, and its derivative
+                    let (pd_decay, d_pd_decay) = match pd >= epsilon {
+                        true => (pd, 1.0),
+                        false => (epsilon.powi(2) / (-pd + 2.0 * epsilon), epsilon.powi(2) / (-pd + 2.0 * epsilon).powi(2)),
+                    };
+                    let m = PI * f64::min(r1, r2);
+                    total += pd_decay * m;
+
+                    if let Some(grad) = grad.as_mut() && d > 0.0 {
+                        //the penetration depth decreases when the poles move apart
+                        let (ux, uy) = ((x1 - x2) / d, (y1 - y2) / d);
+                        let f = -m * d_pd_decay;
+                        add_pole_gradient(&mut grad[i], (f * ux, f * uy), dr1);
+                        add_pole_gradient(&mut grad[j], (-f * ux, -f * uy), dr2);
+                    }
+                }
+            }
+        }
+
+        //poles sticking out of the container
+        let c = &self.container;
+        for (i, poles) in transformed.iter().enumerate() {
+            for &((px, py), r, dr) in poles.iter() {
+                let overhangs = [
+                    (px + r - c.x_max, (1.0, 0.0)),
+                    (c.x_min - (px - r), (-1.0, 0.0)),
+                    (py + r - c.y_max, (0.0, 1.0)),
+                    (c.y_min - (py - r), (0.0, -1.0)),
+                ];
+                for (overhang, (nx, ny)) in overhangs {
+                    if overhang > 0.0 {
+                        let w = self.container_weight * PI * r;
+                        total += w * overhang;
+                        if let Some(grad) = grad.as_mut() {
+                            add_pole_gradient(&mut grad[i], (w * nx, w * ny), dr);
+                        }
+                    }
+                }
+            }
+        }
+
+        total
+    }
+}
+
+/// Adds the gradient with respect to a pole's center to the gradient of its item (translation and rotation)
+fn add_pole_gradient(grad: &mut [f64; 3], (gx, gy): (f64, f64), (drx, dry): (f64, f64)) {
+    grad[0] += gx;
+    grad[1] += gy;
+    grad[2] += gx * drx + gy * dry;
+}
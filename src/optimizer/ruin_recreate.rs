@@ -0,0 +1,144 @@
+use crate::eval::lbf_evaluator::LBFEvaluator;
+use crate::eval::sample_eval::SampleEval;
+use crate::eval::sep_evaluator::SeparationEvaluator;
+use crate::optimizer::separator::Separator;
+use crate::quantify::tracker::CollisionTracker;
+use crate::sample::rotation::RotationModel;
+use crate::sample::search::{search_placement, SampleConfig};
+use itertools::Itertools;
+use jagua_rs::entities::{Instance, Item, PItemKey};
+use jagua_rs::geometry::geo_traits::DistanceTo;
+use jagua_rs::geometry::primitives::Point;
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::probs::spp::entities::{SPPlacement, SPProblem};
+use log::debug;
+use ordered_float::OrderedFloat;
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use std::cmp::Reverse;
+
+/// Configuration of the ruin-and-recreate move, which perturbs the layout when the separator stalls
+#[derive(Debug, Clone, Copy)]
+pub struct RuinRecreateConfig {
+    /// Shape of the region in which all items are removed
+    pub region: RuinRegion,
+    /// Size of the region (radius of the disc or half-width of the band), relative to the largest item diameter of the selected pair
+    pub size_ratio: f64,
+    /// The region is centered around one of this many highest-loss pairs, selected at random
+    pub n_top_pairs: usize,
+    /// Sample configuration used to re-insert the removed items
+    pub sample_config: SampleConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuinRegion {
+    /// Disc around the center of the selected pair
+    Disc,
+    /// Vertical band around the center of the selected pair, spanning the entire height of the container
+    Band,
+}
+
+/// Removes all items in a region around one of the highest-loss pairs and re-inserts them one by one.
+/// Items are re-inserted at their left-bottom-most feasible position (as in the LBF constructor),
+/// or at the position with the least loss if no feasible one is found.
+/// Returns the number of re-inserted items.
+pub fn ruin_and_recreate(sep: &mut Separator, config: &RuinRecreateConfig) -> usize {
+    let Some((center, radius)) = select_region(sep, config) else {
+        return 0;
+    };
+
+    let ruined = sep.prob.layout.placed_items.iter()
+        .filter(|(_, pi)| {
+            let centroid = pi.shape.centroid();
+            match config.region {
+                RuinRegion::Disc => centroid.distance_to(&center) <= radius,
+                RuinRegion::Band => (centroid.0 - center.0).abs() <= radius,
+            }
+        })
+        .map(|(pk, pi)| (pk, SPPlacement::from_placed_item(pi)))
+        //re-insert in the same order as the LBF constructor
+        .sorted_by_cached_key(|(_, p)| {
+            let item_shape = sep.instance.item(p.item_id).shape_cd.as_ref();
+            Reverse(OrderedFloat(item_shape.surrogate().convex_hull_area * item_shape.diameter))
+        })
+        .collect_vec();
+
+    debug!("[R&R] ruining {} items in {:?} around ({:.3}, {:.3}) with radius {:.3}", ruined.len(), config.region, center.0, center.1, radius);
+
+    //recreate on a copy of the problem and tracker, so the separator's own remain valid.
+    //the ruined items are detached from the tracker, their weights are kept at their index until they are re-inserted
+    let mut prob = sep.prob.clone();
+    let mut ct = sep.ct.clone();
+    let ruined_idxs = ruined.iter()
+        .map(|(pk, _)| {
+            prob.remove_item(*pk);
+            ct.pk_idx_map.remove(*pk).unwrap()
+        })
+        .collect_vec();
+
+    let mut new_d_transfs = vec![];
+    for ((_, placement), idx) in ruined.iter().zip(ruined_idxs) {
+        let item = sep.instance.item(placement.item_id);
+        let evaluator = LBFEvaluator::new(&prob.layout, item);
+        let (best_sample, _) = search_placement(&prob.layout, item, None, evaluator, config.sample_config, &sep.rot_model, &mut sep.rng);
+
+        let d_transf = match best_sample {
+            Some((dt, SampleEval::Clear { .. })) => dt,
+            _ => {
+                //place the item back at its original position and search the position with the least loss from there
+                let tmp_pk = prob.place_item(*placement);
+                ct.register_item_at(&prob.layout, tmp_pk, idx);
+                let dt = least_loss_d_transf(&prob, &ct, tmp_pk, item, config.sample_config, &sep.rot_model, &mut sep.rng);
+                prob.remove_item(tmp_pk);
+                ct.pk_idx_map.remove(tmp_pk);
+                dt
+            }
+        };
+        let new_pk = prob.place_item(SPPlacement { d_transf, ..*placement });
+        ct.register_item_at(&prob.layout, new_pk, idx);
+        new_d_transfs.push(d_transf);
+    }
+
+    //apply the new positions to the separator
+    for ((pk, _), d_transf) in ruined.iter().zip(new_d_transfs) {
+        sep.move_item(*pk, d_transf);
+    }
+
+    ruined.len()
+}
+
+/// Selects the center and radius of the region to ruin, around one of the highest-loss pairs (or container collisions)
+fn select_region(sep: &mut Separator, config: &RuinRecreateConfig) -> Option<(Point, f64)> {
+    let layout = &sep.prob.layout;
+    let ct = &sep.ct;
+
+    //only the pairs which are actually colliding are considered
+    let pair_hotspots = ct.colliding_pairs().into_iter()
+        .map(|(pk1, pk2, loss)| {
+            let (pi1, pi2) = (&layout.placed_items[pk1], &layout.placed_items[pk2]);
+            let (c1, c2) = (pi1.shape.centroid(), pi2.shape.centroid());
+            let center = Point((c1.0 + c2.0) / 2.0, (c1.1 + c2.1) / 2.0);
+            (loss, center, f64::max(pi1.shape.diameter, pi2.shape.diameter))
+        });
+
+    let container_hotspots = layout.placed_items.iter()
+        .filter(|(pk, _)| ct.get_container_loss(*pk) > 0.0)
+        .map(|(pk, pi): (PItemKey, _)| (ct.get_container_loss(pk), pi.shape.centroid(), pi.shape.diameter));
+
+    let top_hotspots = pair_hotspots.chain(container_hotspots)
+        .sorted_by_key(|(loss, _, _)| Reverse(OrderedFloat(*loss)))
+        .take(config.n_top_pairs)
+        .collect_vec();
+
+    top_hotspots.choose(&mut sep.rng)
+        .map(|(_, center, diameter)| (*center, diameter * config.size_ratio))
+}
+
+/// Searches the position with the least loss for an already placed item, starting from its current placement.
+/// The loss is weighted by the tracker, which has to be up to date for the item.
+fn least_loss_d_transf(prob: &SPProblem, ct: &CollisionTracker, pk: PItemKey, item: &Item, sample_config: SampleConfig, rot_model: &RotationModel, rng: &mut impl Rng) -> DTransformation {
+    let evaluator = SeparationEvaluator::new(&prob.layout, item, pk, ct);
+    let (best_sample, _) = search_placement(&prob.layout, item, Some(pk), evaluator, sample_config, rot_model, rng);
+
+    best_sample.map_or(prob.layout.placed_items[pk].d_transf, |(dt, _)| dt)
+}
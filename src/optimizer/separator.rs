@@ -1,6 +1,9 @@
+use crate::optimizer::ruin_recreate::{ruin_and_recreate, RuinRecreateConfig};
 use crate::optimizer::worker::{SepStats, SeparatorWorker};
 use crate::optimizer::Terminator;
-use crate::quantify::tracker::{CTSnapshot, CollisionTracker};
+use crate::quantify::tracker::{CTSnapshot, CollisionTracker, WeightConfig};
+use crate::sample::budget::SampleBudgets;
+use crate::sample::rotation::RotationModel;
 use crate::sample::search::SampleConfig;
 use crate::util::assertions::tracker_matches_layout;
 use crate::{FMT};
@@ -16,7 +19,7 @@ use rayon::iter::ParallelIterator;
 use rayon::ThreadPool;
 use jagua_rs::Instant;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use crate::util::listener::{ReportType, SolutionListener};
+use crate::util::listener::{ReportType, SepProgress, SolutionListener};
 
 #[derive(Debug, Clone, Copy)]
 pub struct SeparatorConfig {
@@ -25,6 +28,57 @@ pub struct SeparatorConfig {
     pub n_workers: usize,
     pub log_level: Level,
     pub sample_config: SampleConfig,
+    pub acceptance: AcceptanceCriterion,
+    /// Probability that a colliding item first attempts to swap positions with a similarly sized item
+    pub swap_ratio: f64,
+    /// Ruin-and-recreate move, applied to the best solution every time a strike does not improve it
+    pub ruin_recreate: Option<RuinRecreateConfig>,
+    /// Dynamics of the collision weights, updated after every iteration
+    pub weights: WeightConfig,
+    /// Shape of the container when it is resized, see [`Separator::change_size`]
+    pub container: ContainerMode,
+}
+
+/// Determines how the container is resized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerMode {
+    /// Strip with a fixed height, only the width changes
+    Strip,
+    /// Width and height change together, keeping the container square
+    Square,
+}
+
+/// Determines which moves of the separator workers are accepted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AcceptanceCriterion {
+    /// Items are only moved to placements which do not increase their weighted loss
+    Greedy,
+    /// Items are moved away from their current placement, and worse placements are accepted with a probability
+    /// of `exp(-Δ/T)`, where Δ is the relative increase in weighted loss of the item.
+    /// The temperature starts at `t_init` at every call to [`Separator::separate`] and is multiplied by `t_decay` every iteration.
+    Annealing { t_init: f64, t_decay: f64 },
+}
+
+impl AcceptanceCriterion {
+    pub fn temperature(&self, n_iter: usize) -> f64 {
+        match self {
+            AcceptanceCriterion::Greedy => 0.0,
+            AcceptanceCriterion::Annealing { t_init, t_decay } => t_init * t_decay.powi(n_iter as i32),
+        }
+    }
+}
+
+/// Metropolis criterion on the relative increase in weighted loss of an item: moves which do not increase it are always accepted,
+/// others with a probability of `exp(-Δ/T)`. At a temperature of zero, only non-worsening moves are accepted.
+pub fn accept_move(old_w_l: f64, new_w_l: f64, temperature: f64, rng: &mut impl Rng) -> bool {
+    if new_w_l <= old_w_l {
+        return true;
+    }
+    if temperature <= 0.0 {
+        return false;
+    }
+    let delta = (new_w_l - old_w_l) / old_w_l;
+    rng.random::<f64>() < f64::exp(-delta / temperature)
 }
 
 pub struct Separator {
@@ -34,12 +88,18 @@ pub struct Separator {
     pub ct: CollisionTracker,
     pub workers: Vec<SeparatorWorker>,
     pub config: SeparatorConfig,
+    /// Rotation sampling of all items, synced with the best worker after every iteration
+    pub rot_model: RotationModel,
+    /// Sample budgets of all items, synced with the best worker after every iteration
+    pub budgets: SampleBudgets,
     pub thread_pool: Option<ThreadPool>,
 }
 
 impl Separator {
     pub fn new(instance: SPInstance, prob: SPProblem, mut rng: Xoshiro256PlusPlus, config: SeparatorConfig) -> Self {
         let ct = CollisionTracker::new(&prob.layout);
+        let rot_model = RotationModel::new(instance.items.len(), config.sample_config.rotation);
+        let budgets = SampleBudgets::new(instance.items.len(), config.sample_config);
         let workers = (0..config.n_workers).map(|_|
             SeparatorWorker {
                 instance: instance.clone(),
@@ -47,6 +107,10 @@ impl Separator {
                 ct: ct.clone(),
                 rng: Xoshiro256PlusPlus::seed_from_u64(rng.random()),
                 sample_config: config.sample_config.clone(),
+                rot_model: rot_model.clone(),
+                budgets: budgets.clone(),
+                temperature: 0.0,
+                swap_ratio: config.swap_ratio,
             }).collect();
 
         let pool = if cfg!(target_arch = "wasm32") {
@@ -64,31 +128,47 @@ impl Separator {
             ct,
             workers,
             config,
+            rot_model,
+            budgets,
             thread_pool: pool,
         }
     }
 
-    pub fn change_square_size(&mut self, new_size: f64, split_position: Option<f64>) {
-        let split_position = split_position.unwrap_or(self.prob.strip_width() / 2.0);
-        let delta = new_size - self.prob.strip_width(); // Tính độ lệch size
+    /// Shrinks (or grows) the container to `new_size`, which is either the width of the strip or the side of the square (see [`ContainerMode`]).
+    /// Items beyond the split position(s) are shifted, see [`Separator::change_strip_width`] and [`Separator::change_square_size`].
+    pub fn change_size(&mut self, new_size: f64, split_position: Option<(f64, f64)>) {
+        match self.config.container {
+            ContainerMode::Strip => self.change_strip_width(new_size, split_position.map(|(x, _)| x)),
+            ContainerMode::Square => self.change_square_size(new_size, split_position),
+        }
+    }
+
+    /// Shrinks (or grows) the square container to `new_size`.
+    /// Items to the right of the first split position are shifted along X, items above the second one along Y.
+    pub fn change_square_size(&mut self, new_size: f64, split_position: Option<(f64, f64)>) {
+        let split_position = split_position.unwrap_or((self.prob.strip_width() / 2.0, self.prob.strip_width() / 2.0));
+        let delta = new_size - self.prob.strip_width();
 
-        // Logic dịch chuyển item theo trục X để tránh va chạm dồn cục (giữ nguyên logic heuristic cũ)
+        //shift items right of the vertical split along X, and items above the horizontal split along Y
         let items_to_shift = self.prob.layout.placed_items.iter()
-            .filter(|(_, pi)| pi.shape.centroid().0 > split_position)
-            .map(|(k, pi)| (k, pi.d_transf))
+            .map(|(k, pi)| {
+                let centroid = pi.shape.centroid();
+                let dx = if centroid.0 > split_position.0 { delta } else { 0.0 };
+                let dy = if centroid.1 > split_position.1 { delta } else { 0.0 };
+                (k, pi.d_transf, (dx, dy))
+            })
+            .filter(|(_, _, shift)| *shift != (0.0, 0.0))
             .collect_vec();
 
-        for (pik, dtransf) in items_to_shift {
+        for (pik, dtransf, shift) in items_to_shift {
             let existing_transf = dtransf.compose();
-            // Chỉ dịch chuyển X, để Solver tự lo việc va chạm Y (sẽ hiệu quả hơn là ta tự đoán)
-            let new_transf = existing_transf.translate((delta, 0.0));
+            let new_transf = existing_transf.translate(shift);
             self.move_item(pik, new_transf.decompose());
         }
 
-        // GỌI HÀM MỚI CỦA PROBLEM
         self.prob.change_square_size(new_size);
 
-        // Rebuild tracker và workers như cũ
+        //rebuild the collision tracker and the workers
         self.ct = CollisionTracker::new(&self.prob.layout);
         self.workers.iter_mut().for_each(|opt| {
             *opt = SeparatorWorker {
@@ -97,6 +177,10 @@ impl Separator {
                 ct: self.ct.clone(),
                 rng: Xoshiro256PlusPlus::seed_from_u64(self.rng.random()),
                 sample_config: self.config.sample_config.clone(),
+                rot_model: self.rot_model.clone(),
+                budgets: self.budgets.clone(),
+                temperature: 0.0,
+                swap_ratio: self.config.swap_ratio,
             };
         });
         debug!("[SEP] changed square size to {:.3}", new_size);
@@ -110,28 +194,30 @@ impl Separator {
 
         let mut n_strikes = 0;
         let mut n_iter = 0;
-        let mut sep_stats = SepStats { total_moves: 0, total_evals: 0 };
+        let mut sep_stats = SepStats { total_moves: 0, total_evals: 0, total_uphill_moves: 0, total_swaps: 0 };
         let start = Instant::now();
 
         'outer: while n_strikes < self.config.strike_limit && !term.kill() {
             let mut n_iter_no_improvement = 0;
 
-            let initial_strike_loss = self.ct.get_total_loss();
+            //after a ruin-and-recreate, the current loss can exceed the best one
+            let initial_strike_loss = f64::min(self.ct.get_total_loss(), min_loss);
             debug!("[SEP] [s:{n_strikes},i:{n_iter}]     init_l: {}",FMT().fmt2(initial_strike_loss));
 
-            while n_iter_no_improvement < self.config.iter_no_imprv_limit {
+            while n_iter_no_improvement < self.config.iter_no_imprv_limit && !term.kill() {
                 let (loss_before, w_loss_before) = (
                     self.ct.get_total_loss(),
                     self.ct.get_total_weighted_loss(),
                 );
-                sep_stats += self.move_items_multi();
+                let temperature = self.config.acceptance.temperature(n_iter);
+                sep_stats += self.move_items_multi(temperature);
                 let (loss, w_loss) = (
                     self.ct.get_total_loss(),
                     self.ct.get_total_weighted_loss(),
                 );
 
                 debug!("[SEP] [s:{n_strikes},i:{n_iter}] ( ) l: {} -> {}, wl: {} -> {}, (min l: {})", FMT().fmt2(loss_before), FMT().fmt2(loss), FMT().fmt2(w_loss_before), FMT().fmt2(w_loss), FMT().fmt2(min_loss));
-                debug_assert!(w_loss <= w_loss_before * 1.001, "weighted loss should not increase: {} -> {}", FMT().fmt2(w_loss), FMT().fmt2(w_loss_before));
+                debug_assert!(temperature > 0.0 || w_loss <= w_loss_before * 1.001, "weighted loss should not increase: {} -> {}", FMT().fmt2(w_loss), FMT().fmt2(w_loss_before));
 
                 if loss == 0.0 {
                     //layout is successfully separated
@@ -141,6 +227,7 @@ impl Separator {
                 } else if loss < min_loss {
                     //layout is not separated, but absolute loss is better than before
                     log!(self.config.log_level,"[SEP] [s:{n_strikes},i:{n_iter}] (*) min_l: {}",FMT().fmt2(loss));
+                    sol_listener.report_progress(&SepProgress { total_loss: loss, stats: sep_stats, elapsed: start.elapsed(), finished: false });
                     sol_listener.report(ReportType::ExplImproving, &self.prob.save(), &self.instance);
                     if loss < min_loss * 0.98 {
                         //only reset the iter_no_improvement counter if the loss improved significantly
@@ -152,7 +239,7 @@ impl Separator {
                     n_iter_no_improvement += 1;
                 }
 
-                self.ct.update_weights();
+                self.ct.update_weights(&self.config.weights);
                 n_iter += 1;
             }
 
@@ -162,12 +249,22 @@ impl Separator {
                 n_strikes = 0;
             }
             self.rollback(&min_loss_sol.0, Some(&min_loss_sol.1));
+
+            if let Some(rr_config) = self.config.ruin_recreate
+                && n_strikes > 0 && n_strikes < self.config.strike_limit && !term.kill() {
+                //progress stalled, perturb the best solution before the next strike
+                let n_reinserted = ruin_and_recreate(self, &rr_config);
+                log!(self.config.log_level,"[SEP] [s:{n_strikes},i:{n_iter}] (R&R) re-inserted {} items, l: {}", n_reinserted, FMT().fmt2(self.ct.get_total_loss()));
+            }
         }
+        sol_listener.report_progress(&SepProgress { total_loss: min_loss_sol.1.get_total_loss(), stats: sep_stats, elapsed: start.elapsed(), finished: true });
         let secs = start.elapsed().as_secs_f64();
-        log!(self.config.log_level, "[SEP] finished, evals/s: {} K, evals/move: {}, moves/s: {}, iter/s: {}, #workers: {}, total {:.3}s",
+        log!(self.config.log_level, "[SEP] finished, evals/s: {} K, evals/move: {}, moves/s: {}, uphill moves: {}, swaps: {}, iter/s: {}, #workers: {}, total {:.3}s",
             (sep_stats.total_evals as f64/ (1000.0 * secs)) as usize,
             FMT().fmt2(sep_stats.total_evals as f64 / sep_stats.total_moves as f64),
             FMT().fmt2(sep_stats.total_moves as f64 / secs),
+            sep_stats.total_uphill_moves,
+            sep_stats.total_swaps,
             FMT().fmt2(n_iter as f64 / secs),
             self.workers.len(),
             FMT().fmt2(secs),
@@ -177,13 +274,15 @@ impl Separator {
     }
 
     /// Algorithm 10 from https://doi.org/10.48550/arXiv.2509.13329
-    fn move_items_multi(&mut self) -> SepStats {
+    /// With a temperature above zero, the workers are allowed to accept worse placements (see [`AcceptanceCriterion`])
+    fn move_items_multi(&mut self, temperature: f64) -> SepStats {
         let master_sol = self.prob.save();
 
         let mut separate_multi = || -> SepStats {
             self.workers.par_iter_mut().map(|worker| {
                 // Sync the workers with the master
-                worker.load(&master_sol, &self.ct);
+                worker.load(&master_sol, &self.ct, &self.rot_model, &self.budgets);
+                worker.temperature = temperature;
                 // Let them modify
                 worker.move_items()
             }).sum()
@@ -199,12 +298,14 @@ impl Separator {
         // Check which worker has the lowest total weighted loss
         let best_opt = self.workers.iter_mut()
             .min_by_key(|opt| OrderedFloat(opt.ct.get_total_weighted_loss()))
-            .map(|opt| (opt.prob.save(), &opt.ct))
+            .map(|opt| (opt.prob.save(), &opt.ct, &opt.rot_model, &opt.budgets))
             .unwrap();
 
         // Sync the master with the best optimizer
         self.prob.restore(&best_opt.0);
         self.ct = best_opt.1.clone();
+        self.rot_model.clone_from(best_opt.2);
+        self.budgets.clone_from(best_opt.3);
 
         sep_report
     }
@@ -234,10 +335,10 @@ impl Separator {
         let old_weighted_loss = self.ct.get_weighted_loss(pk);
 
         //Remove the item from the problem
-        self.prob.remove_item(pk);
+        let old_placement = self.prob.remove_item(pk);
 
         //Place the item again but with a new transformation
-        let new_pk = self.prob.place_item(SPPlacement{d_transf,item_id});
+        let new_pk = self.prob.place_item(SPPlacement { d_transf, ..old_placement });
 
         self.ct.register_item_move(&self.prob.layout, pk, new_pk);
 
@@ -282,6 +383,10 @@ impl Separator {
                 ct: self.ct.clone(),
                 rng: Xoshiro256PlusPlus::seed_from_u64(self.rng.random()),
                 sample_config: self.config.sample_config.clone(),
+                rot_model: self.rot_model.clone(),
+                budgets: self.budgets.clone(),
+                temperature: 0.0,
+                swap_ratio: self.config.swap_ratio,
             };
         });
         debug!("[SEP] changed strip width to {:.3}", new_width);
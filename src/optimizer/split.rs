@@ -0,0 +1,148 @@
+use itertools::Itertools;
+use jagua_rs::collision_detection::hazards::collector::{BasicHazardCollector, HazardCollector};
+use jagua_rs::collision_detection::hazards::HazardEntity;
+use jagua_rs::entities::{Layout, PItemKey};
+use jagua_rs::geometry::primitives::{Edge, Point};
+use log::{debug, warn};
+use ordered_float::OrderedFloat;
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+/// Orientation of a line along which the layout is split when the container is resized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitAxis {
+    /// Vertical line (x = pos), items to the right of it are shifted along the x-axis
+    Vertical,
+    /// Horizontal line (y = pos), items above it are shifted along the y-axis
+    Horizontal,
+}
+
+/// Collects all placed items crossed by a split line spanning the entire container, using the quadtree of the `CDEngine`.
+pub fn items_cut_by_line(l: &Layout, axis: SplitAxis, pos: f64) -> Vec<PItemKey> {
+    let bbox = l.container.outer_cd.bbox;
+    let (start, end) = match axis {
+        SplitAxis::Vertical => (Point(pos, bbox.y_min), Point(pos, bbox.y_max)),
+        SplitAxis::Horizontal => (Point(bbox.x_min, pos), Point(bbox.x_max, pos)),
+    };
+    let line = Edge::try_new(start, end).expect("container should not be degenerate");
+
+    let mut collector = BasicHazardCollector::with_capacity(l.placed_items.len() + 1);
+    l.cde().quadtree.collect_collisions(&line, &mut collector);
+
+    collector.iter()
+        .filter_map(|(_, haz)| match haz {
+            HazardEntity::PlacedItem { pk, .. } => Some(*pk),
+            _ => None,
+        })
+        .collect_vec()
+}
+
+/// Criterion to score candidate split lines (lower is better)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitScore {
+    /// Number of items crossed by the line
+    NumberOfItems,
+    /// Total area of the items crossed by the line
+    ItemArea,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SplitConfig {
+    /// Number of random candidate lines scored for every split
+    pub n_candidates: usize,
+    pub score: SplitScore,
+    /// Standard deviation of the (half) normal distribution used to select a candidate from the ranked list.
+    /// Lower values favor the best scoring lines more strongly, higher values add more randomness.
+    pub selection_stddev: f64,
+}
+
+impl SplitConfig {
+    /// At least one candidate and a non-negative standard deviation are required to select a split
+    pub fn is_valid(&self) -> bool {
+        self.n_candidates > 0 && self.selection_stddev >= 0.0
+    }
+}
+
+/// Selects the positions at which the layout is split when the container is shrunk.
+/// Random candidate lines are examined for real gaps in the layout (lines which cross no items at all), one of which is selected at random.
+/// Without any gaps, the candidates are ranked by how much of the layout they cut and better lines are more likely to be selected.
+/// Also tracks the success rate of the shrink attempts it provided splits for.
+pub struct SplitSelector {
+    pub config: SplitConfig,
+    pub n_attempts: usize,
+    pub n_successes: usize,
+}
+
+impl SplitSelector {
+    pub fn new(config: SplitConfig) -> Self {
+        if !config.is_valid() {
+            warn!("[SPLIT] invalid config, the container will be split at its center: {:?}", config);
+        }
+        Self {
+            config,
+            n_attempts: 0,
+            n_successes: 0,
+        }
+    }
+
+    /// Selects a split position along `axis`, or `None` if the config does not allow any selection
+    /// (no candidates or an invalid standard deviation)
+    pub fn select(&self, l: &Layout, axis: SplitAxis, rng: &mut impl Rng) -> Option<f64> {
+        if !self.config.is_valid() {
+            return None;
+        }
+        let bbox = l.container.outer_cd.bbox;
+        let range = match axis {
+            SplitAxis::Vertical => bbox.x_min..bbox.x_max,
+            SplitAxis::Horizontal => bbox.y_min..bbox.y_max,
+        };
+
+        let ranked_candidates = (0..self.config.n_candidates)
+            .map(|_| rng.random_range(range.clone()))
+            .map(|pos| (pos, self.score(l, axis, pos)))
+            .sorted_by_key(|(_, score)| OrderedFloat(*score))
+            .collect_vec();
+
+        //lines which cross no items at all are real gaps in the layout, one of them is always preferred
+        let gaps = ranked_candidates.iter()
+            .take_while(|(_, score)| *score == 0.0)
+            .collect_vec();
+        if let Some((pos, _)) = gaps.choose(rng) {
+            debug!("[SPLIT] selected {:?} split at gap {:.3} ({}/{} candidates are gaps)", axis, pos, gaps.len(), ranked_candidates.len());
+            return Some(*pos);
+        }
+
+        //otherwise, sample a value in range [0.0, 1.0[ from a normal distribution and map it to the ranked candidates
+        let distr = Normal::new(0.0, self.config.selection_stddev).ok()?;
+        let sample = distr.sample(rng).abs().min(0.999);
+        let selected_idx = (sample * ranked_candidates.len() as f64) as usize;
+
+        let (pos, score) = ranked_candidates[selected_idx];
+        debug!("[SPLIT] selected {:?} split at {:.3} (rank: {}/{}, score: {:.3})", axis, pos, selected_idx, ranked_candidates.len(), score);
+        Some(pos)
+    }
+
+    fn score(&self, l: &Layout, axis: SplitAxis, pos: f64) -> f64 {
+        let cut_items = items_cut_by_line(l, axis, pos);
+        match self.config.score {
+            SplitScore::NumberOfItems => cut_items.len() as f64,
+            SplitScore::ItemArea => cut_items.iter().map(|pk| l.placed_items[*pk].shape.area).sum(),
+        }
+    }
+
+    /// Registers the outcome of a shrink attempt which used the selected splits
+    pub fn report(&mut self, success: bool) {
+        self.n_attempts += 1;
+        if success {
+            self.n_successes += 1;
+        }
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        match self.n_attempts {
+            0 => 0.0,
+            n => self.n_successes as f64 / n as f64,
+        }
+    }
+}
@@ -1,15 +1,26 @@
+use crate::consts::SWAP_MAX_AREA_RATIO;
+use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
 use crate::eval::sep_evaluator::SeparationEvaluator;
+use crate::optimizer::separator::accept_move;
 use crate::quantify::tracker::CollisionTracker;
 use crate::sample::search;
+use crate::sample::budget::SampleBudgets;
+use crate::sample::refiner::Refiner;
+use crate::sample::rotation::RotationModel;
 use crate::sample::search::SampleConfig;
+use crate::sample::uniform_sampler::convert_sample_to_closest_feasible;
 use crate::util::assertions::tracker_matches_layout;
 use crate::FMT;
 use itertools::Itertools;
-use jagua_rs::entities::{Instance, PItemKey};
+use jagua_rs::entities::{Instance, Item, PItemKey};
 use jagua_rs::probs::spp::entities::{SPInstance, SPPlacement, SPProblem, SPSolution};
-use jagua_rs::geometry::DTransformation;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::geometry::geo_traits::Transformable;
+use jagua_rs::geometry::primitives::Point;
+use jagua_rs::geometry::{DTransformation, Transformation};
 use log::debug;
-use rand::prelude::SliceRandom;
+use rand::prelude::{IndexedRandom, SliceRandom};
+use rand::Rng;
 use std::iter::Sum;
 use std::ops::AddAssign;
 use rand_xoshiro::Xoshiro256PlusPlus;
@@ -21,56 +32,205 @@ pub struct SeparatorWorker {
     pub ct: CollisionTracker,
     pub rng: Xoshiro256PlusPlus,
     pub sample_config: SampleConfig,
+    /// Rotation sampling of all items, learns from the moves the worker accepts
+    pub rot_model: RotationModel,
+    /// Sample budgets of all items, adapted to the searches of the worker
+    pub budgets: SampleBudgets,
+    /// Temperature of the annealing acceptance criterion, zero means only non-worsening moves are accepted
+    pub temperature: f64,
+    /// Probability that a colliding item first attempts to swap positions with another item
+    pub swap_ratio: f64,
 }
 
 impl SeparatorWorker {
-    pub fn load(&mut self, sol: &SPSolution, ct: &CollisionTracker) {
-        // restores the state of the worker to the given solution and accompanying tracker
+    pub fn load(&mut self, sol: &SPSolution, ct: &CollisionTracker, rot_model: &RotationModel, budgets: &SampleBudgets) {
+        // restores the state of the worker to the given solution, accompanying tracker, rotation model and sample budgets
         debug_assert!(sol.strip_width() == self.prob.strip_width());
         self.prob.restore(sol);
         self.ct = ct.clone();
+        self.rot_model.clone_from(rot_model);
+        self.budgets.clone_from(budgets);
     }
 
     /// Algorithm 5 from https://doi.org/10.48550/arXiv.2509.13329
     pub fn move_items(&mut self) -> SepStats {
         //collect all colliding items and order them randomly
-        let candidates = self.prob.layout.placed_items.keys()
+        let mut candidates = self.prob.layout.placed_items.keys()
             .filter(|pk| self.ct.get_loss(*pk) > 0.0)
             .collect_vec()
             .tap_mut(|v| v.shuffle(&mut self.rng));
 
         let mut total_moves = 0;
         let mut total_evals = 0;
+        let mut total_uphill_moves = 0;
+        let mut total_swaps = 0;
 
         //give each item the opportunity to move to a better (eval) position
-        for &pk in candidates.iter() {
+        for i in 0..candidates.len() {
+            let mut pk = candidates[i];
             //check if the item is still colliding
             if self.ct.get_loss(pk) > 0.0 {
+                if self.rng.random::<f64>() < self.swap_ratio
+                    && let Some(partner_pk) = self.select_swap_partner(pk) {
+                    let (swapped, [new_pk, new_partner_pk], n_evals) = self.swap_items(pk, partner_pk);
+                    total_evals += n_evals;
+                    pk = new_pk;
+                    //the partner is relocated as well, it keeps its own turn under its new key
+                    if let Some(j) = candidates.iter().position(|&c| c == partner_pk) {
+                        candidates[j] = new_partner_pk;
+                    }
+                    if swapped {
+                        total_swaps += 1;
+                        continue;
+                    }
+                }
+
                 let item_id = self.prob.layout.placed_items[pk].item_id;
                 let item = self.instance.item(item_id);
 
                 //create an evaluator to evaluate the samples during the search
-                let evaluator = SeparationEvaluator::new(&self.prob.layout, item, pk, &self.ct);
+                let mut evaluator = SeparationEvaluator::new(&self.prob.layout, item, pk, &self.ct);
+                if self.temperature > 0.0 {
+                    //annealing: the item is forced to leave its current placement
+                    evaluator.exclude(self.prob.layout.placed_items[pk].d_transf);
+                }
 
                 //search for a better position for the item
-                let (best_sample, n_evals) =
-                    search::search_placement(&self.prob.layout, item, Some(pk), evaluator, self.sample_config, &mut self.rng);
+                let sample_config = self.budgets.sample_config(item_id);
+                let (best_sample, search_stats) =
+                    search::search_placement(&self.prob.layout, item, Some(pk), evaluator, sample_config, &self.rot_model, &mut self.rng);
+                self.budgets.record(item_id, &search_stats);
+                total_evals += search_stats.n_evals;
+
+                let (new_dt, eval) = match (best_sample, self.temperature > 0.0) {
+                    (Some(sample), _) => sample,
+                    (None, true) => continue, //no alternative placement found
+                    (None, false) => panic!("search_placement should always return a sample"),
+                };
 
-                let (new_dt, _eval) = best_sample.expect("search_placement should always return a sample");
+                if self.temperature > 0.0 {
+                    let old_w_l = self.ct.get_weighted_loss(pk);
+                    let new_w_l = match eval {
+                        SampleEval::Clear { .. } => 0.0,
+                        SampleEval::Collision { loss } => loss,
+                        SampleEval::Invalid => continue,
+                    };
+                    if !accept_move(old_w_l, new_w_l, self.temperature, &mut self.rng) {
+                        continue;
+                    }
+                    if new_w_l > old_w_l {
+                        total_uphill_moves += 1;
+                    }
+                }
 
+                if item.allowed_rotation == RotationRange::Continuous {
+                    self.rot_model.record(item_id, new_dt.rotation());
+                }
                 //move the item to the new position
                 self.move_item(pk, new_dt);
                 total_moves += 1;
-                total_evals += n_evals;
             }
         }
-        SepStats { total_moves, total_evals }
+        SepStats { total_moves, total_evals, total_uphill_moves, total_swaps }
     }
 
-    pub fn move_item(&mut self, pk: PItemKey, d_transf: DTransformation) -> PItemKey {
-        debug_assert!(tracker_matches_layout(&self.ct, &self.prob.layout));
+    /// Selects a random item of a different type, but of similar size, to swap positions with
+    fn select_swap_partner(&mut self, pk: PItemKey) -> Option<PItemKey> {
+        let item_id = self.prob.layout.placed_items[pk].item_id;
+        let area = self.instance.item(item_id).shape_cd.area;
+
+        self.prob.layout.placed_items.iter()
+            .filter(|(_, pi)| pi.item_id != item_id)
+            .filter(|(_, pi)| {
+                let area_ratio = self.instance.item(pi.item_id).shape_cd.area / area;
+                (1.0 / SWAP_MAX_AREA_RATIO..=SWAP_MAX_AREA_RATIO).contains(&area_ratio)
+            })
+            .map(|(k, _)| k)
+            .collect_vec()
+            .choose(&mut self.rng)
+            .copied()
+    }
+
+    /// Swaps the positions (centroids) of two items, re-samples their rotations and refines both with a short coordinate descent.
+    /// The swap is reverted if it does not reduce the combined weighted loss of both items.
+    /// Returns whether the swap was kept, the new keys of both items and the number of evaluations.
+    fn swap_items(&mut self, pk1: PItemKey, pk2: PItemKey) -> (bool, [PItemKey; 2], usize) {
+        let old_dts = [pk1, pk2].map(|pk| self.prob.layout.placed_items[pk].d_transf);
+        let centroids = [pk1, pk2].map(|pk| self.prob.layout.placed_items[pk].shape.centroid());
+        let old_w_l = self.combined_weighted_loss(pk1, pk2);
+
+        //make room for the first item by moving the second one (with its current rotation) to the first one's position
+        let item_2 = self.instance.item(self.prob.layout.placed_items[pk2].item_id);
+        let pk2 = self.relocate_item(pk2, centered_d_transf(item_2, old_dts[1].rotation(), centroids[0]));
 
+        let (pk1, n_evals_1) = self.swap_into(pk1, centroids[1]);
+        let (pk2, n_evals_2) = self.swap_into(pk2, centroids[0]);
+
+        let new_w_l = self.combined_weighted_loss(pk1, pk2);
+        debug!("[SWAP] combined wl: {} -> {}", FMT().fmt2(old_w_l), FMT().fmt2(new_w_l));
+
+        let swapped = new_w_l < old_w_l;
+        let new_pks = match swapped {
+            true => {
+                for pk in [pk1, pk2] {
+                    let pi = &self.prob.layout.placed_items[pk];
+                    if self.instance.item(pi.item_id).allowed_rotation == RotationRange::Continuous {
+                        self.rot_model.record(pi.item_id, pi.d_transf.rotation());
+                    }
+                }
+                [pk1, pk2]
+            }
+            false => {
+                //revert the swap
+                let pk2 = self.relocate_item(pk2, old_dts[1]);
+                let pk1 = self.relocate_item(pk1, old_dts[0]);
+                [pk1, pk2]
+            }
+        };
+        (swapped, new_pks, n_evals_1 + n_evals_2)
+    }
+
+    /// Moves an item to the best of a set of rotations centered around `target` and refines it with a coordinate descent.
+    /// The rotations are sampled from the rotation model of the item, and the result is clamped to its allowed rotations.
+    fn swap_into(&mut self, pk: PItemKey, target: Point) -> (PItemKey, usize) {
         let item = self.instance.item(self.prob.layout.placed_items[pk].item_id);
+        let current_rotation = self.prob.layout.placed_items[pk].d_transf.rotation();
+
+        let rotations = self.rot_model.sample_rotations(item, &mut self.rng).into_iter()
+            .chain([current_rotation])
+            .collect_vec();
+
+        let mut evaluator = SeparationEvaluator::new(&self.prob.layout, item, pk, &self.ct);
+
+        let best_sample = rotations.into_iter()
+            .map(|r| convert_sample_to_closest_feasible(centered_d_transf(item, r, target), item))
+            .map(|dt| (dt, evaluator.evaluate_sample(dt, None)))
+            .min_by_key(|(_, eval)| *eval)
+            .unwrap();
+
+        let (dt, _) = self.sample_config.pre_refiner.refine(best_sample, &mut evaluator, search::prerefine_cd_config(item, self.rot_model.config(item.id)), &mut self.rng);
+        let dt = convert_sample_to_closest_feasible(dt, item);
+        let n_evals = evaluator.n_evals();
+
+        (self.relocate_item(pk, dt), n_evals)
+    }
+
+    /// Sum of the weighted loss of two items, where the loss between the pair itself is only counted once
+    fn combined_weighted_loss(&self, pk1: PItemKey, pk2: PItemKey) -> f64 {
+        let pair_w_l = self.ct.get_pair_loss(pk1, pk2) * self.ct.get_pair_weight(pk1, pk2);
+        self.ct.get_weighted_loss(pk1) + self.ct.get_weighted_loss(pk2) - pair_w_l
+    }
+
+    /// Moves an item to a new position, without any checks on its loss
+    fn relocate_item(&mut self, pk: PItemKey, d_transf: DTransformation) -> PItemKey {
+        let old_placement = self.prob.remove_item(pk);
+        let new_pk = self.prob.place_item(SPPlacement { d_transf, ..old_placement });
+        self.ct.register_item_move(&self.prob.layout, pk, new_pk);
+        new_pk
+    }
+
+    pub fn move_item(&mut self, pk: PItemKey, d_transf: DTransformation) -> PItemKey {
+        debug_assert!(tracker_matches_layout(&self.ct, &self.prob.layout));
 
         let (old_l, old_w_l) = (self.ct.get_loss(pk), self.ct.get_weighted_loss(pk));
 
@@ -79,7 +239,7 @@ impl SeparatorWorker {
 
         //modify the problem, by removing the item and placing it in the new position
         let old_placement = self.prob.remove_item(pk);
-        let new_placement = SPPlacement { d_transf, item_id: item.id };
+        let new_placement = SPPlacement { d_transf, ..old_placement };
         let new_pk = self.prob.place_item(new_placement);
         //update the collision tracker to reflect the changes
         self.ct.register_item_move(&self.prob.layout, pk, new_pk);
@@ -87,29 +247,44 @@ impl SeparatorWorker {
         let (new_l, new_w_l) = (self.ct.get_loss(new_pk), self.ct.get_weighted_loss(new_pk));
 
         debug!("Moved {:?} (l: {}, wl: {}) to {:?} (l+1: {}, wl+1: {})", old_placement, FMT().fmt2(old_l), FMT().fmt2(old_w_l), new_placement, FMT().fmt2(new_l), FMT().fmt2(new_w_l));
-        debug_assert!(new_w_l <= old_w_l * 1.001, "weighted loss should never increase: {} > {}", FMT().fmt2(old_w_l), FMT().fmt2(new_w_l));
+        debug_assert!(self.temperature > 0.0 || new_w_l <= old_w_l * 1.001, "weighted loss should never increase: {} > {}", FMT().fmt2(old_w_l), FMT().fmt2(new_w_l));
         debug_assert!(tracker_matches_layout(&self.ct, &self.prob.layout));
 
         new_pk
     }
 }
 
+#[derive(Debug, Clone, Copy, Default)]
 pub struct SepStats {
     pub total_moves: usize,
     pub total_evals: usize,
+    /// Number of moves which increased the weighted loss of the item (only with annealing)
+    pub total_uphill_moves: usize,
+    /// Number of accepted item swaps
+    pub total_swaps: usize,
+}
+
+/// Transformation which rotates an item by `rotation` and places its centroid on `target`
+fn centered_d_transf(item: &Item, rotation: f64, target: Point) -> DTransformation {
+    let Point(cx, cy) = item.shape_cd.centroid().transform_clone(&Transformation::from_rotation(rotation));
+    DTransformation::new(rotation, (target.0 - cx, target.1 - cy))
 }
 
 impl Sum for SepStats {
     fn sum<I: Iterator<Item=SepStats>>(iter: I) -> Self {
         let mut total_moves = 0;
         let mut total_evals = 0;
+        let mut total_uphill_moves = 0;
+        let mut total_swaps = 0;
 
         for report in iter {
             total_moves += report.total_moves;
             total_evals += report.total_evals;
+            total_uphill_moves += report.total_uphill_moves;
+            total_swaps += report.total_swaps;
         }
 
-        SepStats { total_moves, total_evals }
+        SepStats { total_moves, total_evals, total_uphill_moves, total_swaps }
     }
 }
 
@@ -117,5 +292,7 @@ impl AddAssign for SepStats {
     fn add_assign(&mut self, other: Self) {
         self.total_moves += other.total_moves;
         self.total_evals += other.total_evals;
+        self.total_uphill_moves += other.total_uphill_moves;
+        self.total_swaps += other.total_swaps;
     }
 }
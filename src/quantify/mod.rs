@@ -1,5 +1,8 @@
-use jagua_rs::geometry::geo_traits::DistanceTo;
-use jagua_rs::geometry::primitives::{Rect, SPolygon};
+use itertools::Itertools;
+use jagua_rs::entities::LayoutSnapshot;
+use jagua_rs::geometry::geo_traits::{CollidesWith, DistanceTo};
+use jagua_rs::geometry::intersection::intersection_area;
+use jagua_rs::geometry::primitives::SPolygon;
 use crate::consts::OVERLAP_PROXY_EPSILON_DIAM_RATIO;
 use crate::quantify::overlap_proxy::overlap_area_proxy;
 
@@ -11,17 +14,21 @@ pub mod simd;
 
 /// Quantifies a collision between two simple polygons.
 /// Algorithm 4 from https://doi.org/10.48550/arXiv.2509.13329
+/// With the `exact_loss` feature enabled, the exact intersection area replaces the pole-based overlap proxy.
 #[inline(always)]
 pub fn quantify_collision_poly_poly(s1: &SPolygon, s2: &SPolygon) -> f64 {
     let epsilon = f64::max(s1.diameter, s2.diameter) * OVERLAP_PROXY_EPSILON_DIAM_RATIO;
 
-    let overlap_proxy = overlap_area_proxy(&s1.surrogate(), &s2.surrogate(), epsilon) + epsilon.powi(2);
+    #[cfg(not(feature = "exact_loss"))]
+    let overlap = overlap_area_proxy(&s1.surrogate(), &s2.surrogate(), epsilon) + epsilon.powi(2);
+    #[cfg(feature = "exact_loss")]
+    let overlap = intersection_area(s1, s2) + epsilon.powi(2);
 
-    debug_assert!(overlap_proxy.is_normal());
+    debug_assert!(overlap.is_normal());
 
     let penalty = calc_shape_penalty(s1, s2);
 
-    overlap_proxy.sqrt() * penalty
+    overlap.sqrt() * penalty
 }
 
 pub fn calc_shape_penalty(s1: &SPolygon, s2: &SPolygon) -> f64 {
@@ -31,18 +38,17 @@ pub fn calc_shape_penalty(s1: &SPolygon, s2: &SPolygon) -> f64 {
 }
 
 /// Quantifies a collision between a simple polygon and the exterior of the container.
+/// Based on the exact area of the item that sticks out of the container's shape, so it also holds for non-rectangular containers.
 #[inline(always)]
-pub fn quantify_collision_poly_container(s: &SPolygon, c_bbox: Rect) -> f64 {
-    let s_bbox = s.bbox;
-    let overlap = match Rect::intersection(s_bbox, c_bbox) {
-        Some(r) => {
-            //intersection exist, calculate the area of the intersection (+ a small value to ensure it is never zero)
-            let negative_area = (s_bbox.area() - r.area()) + 0.0001 * s_bbox.area();
-            negative_area
-        }
-        None => {
+pub fn quantify_collision_poly_container(s: &SPolygon, c: &SPolygon) -> f64 {
+    let overlap = match intersection_area(s, c) {
+        0.0 => {
             //no intersection, guide towards intersection with container
-            s_bbox.area() + s_bbox.centroid().distance_to(&c_bbox.centroid())
+            s.area + s.bbox.centroid().distance_to(&c.bbox.centroid())
+        }
+        inside_area => {
+            //area outside the container (+ a small value to ensure it is never zero)
+            f64::max(s.area - inside_area, 0.0) + 0.0001 * s.area
         }
     };
     debug_assert!(overlap.is_normal());
@@ -50,4 +56,14 @@ pub fn quantify_collision_poly_container(s: &SPolygon, c_bbox: Rect) -> f64 {
     let penalty = calc_shape_penalty(s, s);
 
     2.0 * overlap.sqrt() * penalty
-}
\ No newline at end of file
+}
+
+/// Sums the exact intersection area over all pairs of placed items in the layout.
+/// Unlike the loss, this is a true geometric measure of how far the layout is from feasibility.
+pub fn total_overlap_area(ls: &LayoutSnapshot) -> f64 {
+    ls.placed_items.values()
+        .tuple_combinations()
+        .filter(|(pi1, pi2)| pi1.shape.bbox.collides_with(&pi2.shape.bbox))
+        .map(|(pi1, pi2)| intersection_area(&pi1.shape, &pi2.shape))
+        .sum()
+}
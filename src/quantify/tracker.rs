@@ -1,10 +1,10 @@
 use jagua_rs::collision_detection::hazards::collector::{BasicHazardCollector, HazardCollector};
 use jagua_rs::collision_detection::hazards::HazardEntity;
 use jagua_rs::entities::{Layout, PItemKey};
-use crate::consts::{GLS_WEIGHT_DECAY, GLS_WEIGHT_MAX_INC_RATIO, GLS_WEIGHT_MIN_INC_RATIO};
 use crate::quantify::pair_matrix::PairMatrix;
 use crate::quantify::{quantify_collision_poly_container, quantify_collision_poly_poly};
 use crate::util::assertions::tracker_matches_layout;
+use itertools::Itertools;
 use ordered_float::Float;
 use slotmap::SecondaryMap;
 
@@ -73,7 +73,7 @@ impl CollisionTracker {
                     self.pair_collisions[(idx, idx_other)].loss = loss;
                 }
                 HazardEntity::Exterior => {
-                    let loss = quantify_collision_poly_container(shape, l.container.outer_cd.bbox);
+                    let loss = quantify_collision_poly_container(shape, &l.container.outer_cd);
                     assert!(loss > 0.0, "loss for a collision should be > 0.0");
                     self.container_collisions[idx].loss = loss;
                 }
@@ -101,28 +101,61 @@ impl CollisionTracker {
     pub fn register_item_move(&mut self, l: &Layout, old_pk: PItemKey, new_pk: PItemKey) {
         //swap the keys in the pk_idx_map
         let idx = self.pk_idx_map.remove(old_pk).unwrap();
-        self.pk_idx_map.insert(new_pk, idx);
+        self.register_item_at(l, new_pk, idx);
+    }
+
+    /// Registers a placed item at an index of the tracker which is not mapped to any other item,
+    /// keeping the weights stored at that index
+    pub fn register_item_at(&mut self, l: &Layout, pk: PItemKey, idx: usize) {
+        self.pk_idx_map.insert(pk, idx);
 
-        self.recompute_loss_for_item(new_pk, l);
+        self.recompute_loss_for_item(pk, l);
 
         debug_assert!(tracker_matches_layout(self, l));
     }
 
 
-    /// Algorithm 8 from https://doi.org/10.48550/arXiv.2509.13329
-    pub fn update_weights(&mut self) {
+    /// Algorithm 8 from https://doi.org/10.48550/arXiv.2509.13329, with the dynamics defined by `config`
+    pub fn update_weights(&mut self, config: &WeightConfig) {
         let max_loss = self.pair_collisions.data.iter()
             .chain(self.container_collisions.iter())
             .map(|e| e.loss)
             .fold(0.0, |a, b| a.max(b));
 
-        for e in self.pair_collisions.data.iter_mut()
-            .chain(self.container_collisions.iter_mut()) {
+        //with a top-k selection, only the pairs with a loss at least as large as the k-th largest one are increased
+        let pair_loss_threshold = match config.selection {
+            WeightSelection::AllColliding => 0.0,
+            WeightSelection::TopK(k) => {
+                let pair_losses = self.pair_collisions.data.iter()
+                    .map(|e| e.loss)
+                    .filter(|&l| l > 0.0)
+                    .sorted_unstable_by(|a, b| b.total_cmp(a))
+                    .collect_vec();
+                match k {
+                    0 => f64::INFINITY,
+                    _ => pair_losses.get(k - 1).copied().unwrap_or(0.0),
+                }
+            }
+        };
+
+        let pair_schedule = &config.pair;
+        let container_schedule = config.container.as_ref().unwrap_or(&config.pair);
+
+        let pair_entries = self.pair_collisions.data.iter_mut()
+            .map(|e| (e, pair_schedule, pair_loss_threshold));
+        let container_entries = self.container_collisions.iter_mut()
+            .map(|e| (e, container_schedule, 0.0));
+
+        for (e, schedule, loss_threshold) in pair_entries.chain(container_entries) {
             let multiplier = match e.loss == 0.0 {
-                true => GLS_WEIGHT_DECAY, // no collision
-                false => GLS_WEIGHT_MIN_INC_RATIO + (GLS_WEIGHT_MAX_INC_RATIO - GLS_WEIGHT_MIN_INC_RATIO) * (e.loss / max_loss),
+                true => schedule.decay, // no collision
+                false if e.loss < loss_threshold => 1.0, // colliding, but not among the selected pairs
+                false => schedule.min_inc_ratio + (schedule.max_inc_ratio - schedule.min_inc_ratio) * (e.loss / max_loss),
             };
-            e.weight = (e.weight * multiplier).max(1.0);
+            e.weight = (e.weight * multiplier).max(config.min_weight);
+            if let Some(max_weight) = config.max_weight {
+                e.weight = e.weight.min(max_weight);
+            }
         }
     }
 
@@ -147,6 +180,25 @@ impl CollisionTracker {
         self.container_collisions[idx].loss
     }
 
+    /// Returns all pairs of items which are currently colliding, together with their loss
+    pub fn colliding_pairs(&self) -> Vec<(PItemKey, PItemKey, f64)> {
+        let mut idx_pk_map = vec![None; self.size];
+        for (pk, &idx) in self.pk_idx_map.iter() {
+            idx_pk_map[idx] = Some(pk);
+        }
+
+        let mut pairs = vec![];
+        for i in 0..self.size {
+            for j in (i + 1)..self.size {
+                let loss = self.pair_collisions[(i, j)].loss;
+                if loss > 0.0 && let (Some(pk1), Some(pk2)) = (idx_pk_map[i], idx_pk_map[j]) {
+                    pairs.push((pk1, pk2, loss));
+                }
+            }
+        }
+        pairs
+    }
+
     pub fn get_loss(&self, pk: PItemKey) -> f64 {
         let idx = self.pk_idx_map[pk];
 
@@ -190,6 +242,41 @@ impl CollisionTracker {
     }
 }
 
+/// Dynamics of the weights of the guided local search, see [`CollisionTracker::update_weights`]
+#[derive(Debug, Clone, Copy)]
+pub struct WeightConfig {
+    /// Schedule of the weights of colliding pairs of items
+    pub pair: WeightSchedule,
+    /// Schedule of the weights of collisions with the container, same as `pair` if `None`
+    pub container: Option<WeightSchedule>,
+    /// Which colliding pairs have their weights increased
+    pub selection: WeightSelection,
+    /// Lower bound of all weights
+    pub min_weight: f64,
+    /// Upper bound of all weights, uncapped if `None`
+    pub max_weight: Option<f64>,
+}
+
+/// Multipliers applied to a weight every time the weights are updated
+#[derive(Debug, Clone, Copy)]
+pub struct WeightSchedule {
+    /// Multiplier for the collision with the largest loss
+    pub max_inc_ratio: f64,
+    /// Multiplier for a collision with (almost) no loss, interpolated linearly up to `max_inc_ratio`
+    pub min_inc_ratio: f64,
+    /// Multiplier when there is no collision
+    pub decay: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightSelection {
+    /// The weights of all colliding pairs are increased
+    AllColliding,
+    /// Only the weights of the `k` pairs with the largest loss are increased, those of other colliding pairs remain unchanged.
+    /// Collisions with the container are not affected.
+    TopK(usize),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CTEntry {
     pub loss: f64,
@@ -0,0 +1,112 @@
+use crate::sample::search::{SampleConfig, SampleSource, SearchStats};
+
+/// Configuration of the per-item adaptation of the sample budgets
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveBudgetConfig {
+    /// Weight of every new observation in the exponential moving averages of the statistics
+    pub smoothing: f64,
+    /// Minimum share of the samples (container + focussed) assigned to either type of sample
+    pub min_share: f64,
+    /// Minimum and maximum number of coordinate descents
+    pub n_coord_descents_range: (usize, usize),
+}
+
+/// Sample budgets of every item, adapted to the statistics of its searches (see [`SearchStats`]):
+/// - the total number of samples is divided between container and focussed samples according to the share of searches
+///   in which each type of sample produced the best sample.
+/// - the number of coordinate descents scales with the relative loss reduction the coordinate descents achieve.
+#[derive(Debug, Clone)]
+pub struct SampleBudgets {
+    base: SampleConfig,
+    adaptive: Option<AdaptiveBudgetConfig>,
+    stats: Vec<ItemSampleStats>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ItemSampleStats {
+    /// Moving average of how often the focussed samples produced the best sample (versus the container samples)
+    focussed_share: f64,
+    /// Moving average of the relative loss reduction achieved by the coordinate descents
+    cd_gain: f64,
+    n_searches: usize,
+}
+
+impl SampleBudgets {
+    pub fn new(n_items: usize, base: SampleConfig) -> Self {
+        let adaptive = base.adaptive_budget;
+        let n_samples = (base.n_container_samples + base.n_focussed_samples).max(1);
+        let init_stats = ItemSampleStats {
+            focussed_share: base.n_focussed_samples as f64 / n_samples as f64,
+            cd_gain: adaptive.map_or(0.0, |a| {
+                let (min, max) = a.n_coord_descents_range;
+                match max > min {
+                    true => (base.n_coord_descents.clamp(min, max) - min) as f64 / (max - min) as f64,
+                    false => 0.0,
+                }
+            }),
+            n_searches: 0,
+        };
+        Self {
+            base,
+            adaptive,
+            stats: vec![init_stats; n_items],
+        }
+    }
+
+    /// Sample configuration to use for the next search of an item
+    pub fn sample_config(&self, item_id: usize) -> SampleConfig {
+        let Some(adaptive) = self.adaptive else {
+            return self.base;
+        };
+        let stats = &self.stats[item_id];
+
+        let n_samples = self.base.n_container_samples + self.base.n_focussed_samples;
+        let focussed_share = stats.focussed_share.clamp(adaptive.min_share, 1.0 - adaptive.min_share);
+        let n_focussed_samples = (n_samples as f64 * focussed_share).round() as usize;
+
+        let (min_cd, max_cd) = adaptive.n_coord_descents_range;
+        let n_coord_descents = (min_cd + (stats.cd_gain * max_cd.saturating_sub(min_cd) as f64).round() as usize).max(1);
+
+        SampleConfig {
+            n_container_samples: n_samples - n_focussed_samples,
+            n_focussed_samples,
+            n_coord_descents,
+            ..self.base
+        }
+    }
+
+    /// Updates the statistics of an item with the outcome of one of its searches
+    pub fn record(&mut self, item_id: usize, search_stats: &SearchStats) {
+        let Some(adaptive) = self.adaptive else {
+            return;
+        };
+        let stats = &mut self.stats[item_id];
+        let ema = |avg: &mut f64, obs: f64| *avg += adaptive.smoothing * (obs - *avg);
+
+        match search_stats.best_source {
+            Some(SampleSource::Focussed) => ema(&mut stats.focussed_share, 1.0),
+            Some(SampleSource::Container) => ema(&mut stats.focussed_share, 0.0),
+            //the reference placement itself remained the best, no information on the samplers
+            Some(SampleSource::Reference) | None => (),
+        }
+        ema(&mut stats.cd_gain, search_stats.cd_gain);
+        stats.n_searches += 1;
+    }
+
+    /// Takes over the learned statistics of another set of budgets
+    pub fn adopt_stats(&mut self, other: &SampleBudgets) {
+        if self.adaptive.is_some() && other.adaptive.is_some() {
+            self.stats.clone_from(&other.stats);
+        }
+    }
+
+    /// Human-readable summary of the learned budget of an item, if the budgets are adaptive
+    pub fn format_budget(&self, item_id: usize) -> Option<String> {
+        self.adaptive?;
+        let config = self.sample_config(item_id);
+        let stats = &self.stats[item_id];
+        Some(format!("container: {}, focussed: {}, coord descents: {} (focussed share: {:.3}, cd gain: {:.3}, searches: {})",
+            config.n_container_samples, config.n_focussed_samples, config.n_coord_descents,
+            stats.focussed_share, stats.cd_gain, stats.n_searches))
+    }
+}
@@ -1,13 +1,13 @@
 use crate::consts::{CD_STEP_FAIL, CD_STEP_SUCCESS};
 use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
-use jagua_rs::geometry::DTransformation;
+use jagua_rs::geometry::{closest_rotation_in_intervals, DTransformation};
 use log::trace;
 use rand::Rng;
 use std::cmp::Ordering;
 use std::fmt::Debug;
 
 #[derive(Clone, Debug, Copy)]
-pub struct CDConfig {
+pub struct CDConfig<'a> {
     /// Initial step size for the coordinate descent
     pub t_step_init: f64,
     /// Limit for the step size, below which no more candidates are generated
@@ -18,6 +18,18 @@ pub struct CDConfig {
     pub r_step_limit: f64,
     /// Defines whether the wiggle axis (rotation) is enabled
     pub wiggle: bool,
+    /// Intervals to which the rotation is clamped on the wiggle axis, unrestricted if `None`
+    pub r_intervals: Option<&'a [(f64, f64)]>,
+}
+
+impl CDConfig<'_> {
+    /// Clamps a rotation to the allowed intervals, if any
+    pub fn clamp_rotation(&self, r: f64) -> f64 {
+        match self.r_intervals {
+            Some(intervals) => closest_rotation_in_intervals(r, intervals),
+            None => r,
+        }
+    }
 }
 
 /// Refines an initial 'sample' (transformation and evaluation) into a local minimum using a coordinate descent inspired algorithm.
@@ -40,6 +52,7 @@ pub fn refine_coord_desc(
         r_step: cd_config.r_step_init,
         r_step_limit: cd_config.r_step_limit,
         wiggle: cd_config.wiggle,
+        r_intervals: cd_config.r_intervals,
     };
 
     // From the CD state, ask for candidate positions to evaluate. If none provided, stop.
@@ -62,7 +75,7 @@ pub fn refine_coord_desc(
 }
 
 #[derive(Debug)]
-struct CoordinateDescent {
+struct CoordinateDescent<'a> {
     /// The current position in the coordinate descent
     pub pos: DTransformation,
     /// The current evaluation of the position
@@ -79,9 +92,11 @@ struct CoordinateDescent {
     pub r_step_limit: f64,
     /// Defines whether the wiggle axis is enabled
     pub wiggle: bool,
+    /// Intervals to which the rotation is clamped on the wiggle axis
+    pub r_intervals: Option<&'a [(f64, f64)]>,
 }
 
-impl CoordinateDescent {
+impl CoordinateDescent<'_> {
 
     /// Generates candidates to be evaluated. 
     pub fn ask(&self) -> Option<[DTransformation; 2]> {
@@ -100,7 +115,13 @@ impl CoordinateDescent {
                 CDAxis::Vertical => [(tx, ty + sy, r), (tx, ty - sy, r)],
                 CDAxis::ForwardDiag => [(tx + sx, ty + sy, r), (tx - sx, ty - sy, r)],
                 CDAxis::BackwardDiag => [(tx - sx, ty + sy, r), (tx + sx, ty - sy, r)],
-                CDAxis::Wiggle => [(tx, ty, r + sr), (tx, ty, r - sr)]
+                CDAxis::Wiggle => {
+                    let clamp = |r: f64| match self.r_intervals {
+                        Some(intervals) => closest_rotation_in_intervals(r, intervals),
+                        None => r,
+                    };
+                    [(tx, ty, clamp(r + sr)), (tx, ty, clamp(r - sr))]
+                }
             };
             
             let c = transformations.map(|(tx, ty, r)| {
@@ -0,0 +1,58 @@
+use crate::sample::sampler::Sampler;
+use crate::sample::uniform_sampler::UniformBBoxSampler;
+use jagua_rs::collision_detection::quadtree::{QTHazPresence, QTNode};
+use jagua_rs::collision_detection::CDEngine;
+use jagua_rs::geometry::primitives::Rect;
+use jagua_rs::geometry::DTransformation;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::Rng;
+
+/// Samples transformations biased towards the free space of a layout.
+/// A leaf cell of the quadtree is selected with a probability proportional to its area,
+/// divided by one plus the number of hazards partially present in it. Cells entirely covered by a hazard are never selected.
+/// The item's reference point is then sampled uniformly within the selected cell.
+pub struct FreeSpaceSampler<'a> {
+    base: &'a UniformBBoxSampler,
+    cells: Vec<Rect>,
+    distr: Option<WeightedIndex<f64>>,
+}
+
+impl<'a> FreeSpaceSampler<'a> {
+    pub fn new(base: &'a UniformBBoxSampler, cde: &CDEngine) -> Self {
+        let mut cells = vec![];
+        let mut weights = vec![];
+        collect_free_cells(&cde.quadtree, &mut cells, &mut weights);
+
+        let distr = WeightedIndex::new(&weights).ok();
+        Self { base, cells, distr }
+    }
+}
+
+impl Sampler for FreeSpaceSampler<'_> {
+    fn sample(&mut self, rng: &mut impl Rng) -> DTransformation {
+        let cell_sample = self.distr.as_ref()
+            .and_then(|distr| self.base.sample_within(self.cells[distr.sample(rng)], rng));
+
+        //fall back to a uniform sample if there is no free space, or the item cannot be placed within the selected cell
+        cell_sample.unwrap_or_else(|| self.base.sample(rng))
+    }
+}
+
+fn collect_free_cells(node: &QTNode, cells: &mut Vec<Rect>, weights: &mut Vec<f64>) {
+    match &node.children {
+        Some(children) => children.iter().for_each(|c| collect_free_cells(c, cells, weights)),
+        None => {
+            let mut n_partial = 0;
+            for haz in node.hazards.iter() {
+                match haz.presence {
+                    QTHazPresence::Entire => return,
+                    QTHazPresence::Partial(_) => n_partial += 1,
+                    QTHazPresence::None => (),
+                }
+            }
+            cells.push(node.bbox);
+            weights.push(node.bbox.area() / (1 + n_partial) as f64);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::sample::sampler::Sampler;
+use crate::sample::uniform_sampler::UniformBBoxSampler;
+use jagua_rs::geometry::DTransformation;
+use rand::Rng;
+
+/// Sequence of points in the unit cube [0, 1)³ which covers it more evenly than independent uniform samples
+pub trait LowDiscrepancySequence {
+    fn next_point(&mut self) -> [f64; 3];
+}
+
+/// Halton sequence in bases 2, 3 and 5, randomized with a Cranley-Patterson rotation (random shift modulo 1)
+#[derive(Debug, Clone)]
+pub struct HaltonSequence {
+    index: u64,
+    shift: [f64; 3],
+}
+
+impl HaltonSequence {
+    const BASES: [u64; 3] = [2, 3, 5];
+
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Self {
+            index: 1,
+            shift: [rng.random(), rng.random(), rng.random()],
+        }
+    }
+}
+
+impl LowDiscrepancySequence for HaltonSequence {
+    fn next_point(&mut self) -> [f64; 3] {
+        let point = [0, 1, 2].map(|d| (radical_inverse(self.index, Self::BASES[d]) + self.shift[d]).fract());
+        self.index += 1;
+        point
+    }
+}
+
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let mut result = 0.0;
+    let mut f = 1.0 / base as f64;
+    while index > 0 {
+        result += f * (index % base) as f64;
+        index /= base;
+        f /= base as f64;
+    }
+    result
+}
+
+/// Three-dimensional Sobol sequence (direction numbers of Joe and Kuo), randomized with a random digital shift
+#[derive(Debug, Clone)]
+pub struct SobolSequence {
+    index: u32,
+    state: [u32; 3],
+    directions: [[u32; 32]; 3],
+}
+
+impl SobolSequence {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Self {
+            index: 0,
+            state: [rng.random(), rng.random(), rng.random()],
+            directions: [
+                sobol_directions(0, 0, &[]),
+                sobol_directions(1, 0, &[1]),
+                sobol_directions(2, 1, &[1, 3]),
+            ],
+        }
+    }
+}
+
+impl LowDiscrepancySequence for SobolSequence {
+    fn next_point(&mut self) -> [f64; 3] {
+        let point = self.state.map(|s| s as f64 / (1u64 << 32) as f64);
+        //gray code construction: flip the direction number of the lowest zero bit of the index
+        let c = (!self.index).trailing_zeros().min(31) as usize;
+        for d in 0..3 {
+            self.state[d] ^= self.directions[d][c];
+        }
+        self.index = self.index.wrapping_add(1);
+        point
+    }
+}
+
+/// Direction numbers for a dimension with a primitive polynomial of degree `s` and coefficients `a`,
+/// with initial direction numbers `m`. A degree of zero is the van der Corput sequence.
+fn sobol_directions(s: usize, a: u32, m: &[u32]) -> [u32; 32] {
+    let mut v = [0u32; 32];
+    if s == 0 {
+        for (i, v) in v.iter_mut().enumerate() {
+            *v = 1 << (31 - i);
+        }
+        return v;
+    }
+    for i in 0..s {
+        v[i] = m[i] << (31 - i);
+    }
+    for i in s..32 {
+        v[i] = v[i - s] ^ (v[i - s] >> s);
+        for k in 1..s {
+            v[i] ^= ((a >> (s - 1 - k)) & 1) * v[i - k];
+        }
+    }
+    v
+}
+
+/// Samples transformations by mapping the points of a low-discrepancy sequence to the ranges of a [`UniformBBoxSampler`]
+pub struct LowDiscrepancySampler<'a, S: LowDiscrepancySequence> {
+    base: &'a UniformBBoxSampler,
+    sequence: S,
+}
+
+impl<'a, S: LowDiscrepancySequence> LowDiscrepancySampler<'a, S> {
+    pub fn new(base: &'a UniformBBoxSampler, sequence: S) -> Self {
+        Self { base, sequence }
+    }
+}
+
+impl<S: LowDiscrepancySequence> Sampler for LowDiscrepancySampler<'_, S> {
+    fn sample(&mut self, _rng: &mut impl Rng) -> DTransformation {
+        self.base.sample_from_unit(self.sequence.next_point())
+    }
+}
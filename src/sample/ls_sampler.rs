@@ -0,0 +1,72 @@
+use crate::sample::sampler::Sampler;
+use jagua_rs::entities::Item;
+use jagua_rs::geometry::closest_rotation_in_intervals;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use jagua_rs::geometry::DTransformation;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+/// Local search sampler, ported from the `LSSampler` of the lbf crate.
+/// Samples are drawn from normal distributions around the best transformation found so far,
+/// with standard deviations which decay exponentially over the course of the samples.
+/// Each time an improvement is found, the mean of the distributions is shifted to the new best transformation.
+#[derive(Debug, Clone)]
+pub struct LSSampler {
+    mean: DTransformation,
+    allowed_rotation: RotationRange,
+    /// Initial and final standard deviation of the translation
+    sd_transl_range: (f64, f64),
+    /// Initial and final standard deviation of the rotation
+    sd_rot_range: (f64, f64),
+    n_samples: usize,
+    n_samples_total: usize,
+}
+
+impl LSSampler {
+    pub fn new(item: &Item, ref_transform: DTransformation, sd_transl_range: (f64, f64), sd_rot_range: (f64, f64), n_samples_total: usize) -> Self {
+        Self {
+            mean: ref_transform,
+            allowed_rotation: item.allowed_rotation.clone(),
+            sd_transl_range,
+            sd_rot_range,
+            n_samples: 0,
+            n_samples_total,
+        }
+    }
+
+    /// Standard deviations of the translation and rotation, following an exponential decay curve:
+    /// f(x) = init * (end/init)^x, with x the fraction of samples drawn so far.
+    fn stddevs(&self) -> (f64, f64) {
+        let progress = self.n_samples as f64 / self.n_samples_total.max(1) as f64;
+        let decay = |(init, end): (f64, f64)| init * (end / init).powf(progress);
+        (decay(self.sd_transl_range), decay(self.sd_rot_range))
+    }
+}
+
+impl Sampler for LSSampler {
+    fn sample(&mut self, rng: &mut impl Rng) -> DTransformation {
+        let (sd_transl, sd_rot) = self.stddevs();
+        self.n_samples += 1;
+
+        let (tx, ty) = self.mean.translation();
+        let x = Normal::new(tx, sd_transl).unwrap().sample(rng);
+        let y = Normal::new(ty, sd_transl).unwrap().sample(rng);
+
+        //discrete rotations are never changed, only their mean is used
+        let r = match &self.allowed_rotation {
+            RotationRange::None => 0.0,
+            RotationRange::Discrete(_) => self.mean.rotation(),
+            RotationRange::Continuous => Normal::new(self.mean.rotation(), sd_rot).unwrap().sample(rng),
+            RotationRange::Intervals(intervals) => {
+                let r = Normal::new(self.mean.rotation(), sd_rot).unwrap().sample(rng);
+                closest_rotation_in_intervals(r, intervals)
+            }
+        };
+
+        DTransformation::new(r, (x, y))
+    }
+
+    fn notify_improvement(&mut self, dt: DTransformation) {
+        self.mean = dt;
+    }
+}
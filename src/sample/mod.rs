@@ -1,4 +1,14 @@
 mod best_samples;
-mod coord_descent;
+pub mod budget;
+pub mod coord_descent;
+pub mod free_space_sampler;
+pub mod low_discrepancy;
+pub mod ls_sampler;
+pub mod nelder_mead;
+pub mod pattern_search;
+pub mod refiner;
+pub mod rotation;
+pub mod sampler;
 pub mod search;
+pub mod slide;
 pub mod uniform_sampler;
\ No newline at end of file
@@ -0,0 +1,83 @@
+use crate::consts::NM_MAX_ITERATIONS;
+use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
+use crate::sample::coord_descent::CDConfig;
+use jagua_rs::geometry::DTransformation;
+use log::trace;
+
+/// Refines an initial 'sample' (transformation and evaluation) into a local minimum using the Nelder-Mead simplex method
+/// (with the standard coefficients: reflection 1, expansion 2, contraction 0.5 and shrink 0.5).
+/// The simplex spans (x, y, r) if the item can be wiggled and (x, y) otherwise. The initial simplex extends
+/// from the sample by the initial step sizes along each axis, and the search stops once all vertices lie within
+/// the limit step sizes of the best vertex, or after [`NM_MAX_ITERATIONS`] iterations.
+pub fn refine_nelder_mead(
+    (init_dt, init_eval): (DTransformation, SampleEval),
+    evaluator: &mut impl SampleEvaluator,
+    config: CDConfig,
+) -> (DTransformation, SampleEval) {
+    let n_evals_init = evaluator.n_evals();
+    let n_dims = if config.wiggle { 3 } else { 2 };
+    let to_dt = |p: [f64; 3]| DTransformation::new(config.clamp_rotation(p[2]), (p[0], p[1]));
+
+    let (tx, ty) = init_dt.translation();
+    let origin = [tx, ty, init_dt.rotation()];
+    let mut simplex = vec![(origin, init_eval)];
+    for d in 0..n_dims {
+        let mut p = origin;
+        p[d] += if d < 2 { config.t_step_init } else { config.r_step_init };
+        simplex.push((p, evaluator.evaluate_sample(to_dt(p), None)));
+    }
+
+    for _ in 0..NM_MAX_ITERATIONS {
+        simplex.sort_by_key(|(_, eval)| *eval);
+        let (best, best_eval) = simplex[0];
+        let (worst, worst_eval) = simplex[n_dims];
+        let second_worst_eval = simplex[n_dims - 1].1;
+
+        let converged = simplex.iter().all(|(p, _)| {
+            (p[0] - best[0]).abs() < config.t_step_limit
+                && (p[1] - best[1]).abs() < config.t_step_limit
+                && (p[2] - best[2]).abs() < config.r_step_limit
+        });
+        if converged {
+            break;
+        }
+
+        //centroid of all vertices except the worst one
+        let mut centroid = [0.0; 3];
+        for (p, _) in &simplex[..n_dims] {
+            for d in 0..3 {
+                centroid[d] += p[d] / n_dims as f64;
+            }
+        }
+        let along = |coef: f64| [0, 1, 2].map(|d| centroid[d] + coef * (centroid[d] - worst[d]));
+        //evaluations exceeding the worst vertex are never accepted, so they can be cut short
+        let mut eval_bounded = |p: [f64; 3]| (p, evaluator.evaluate_sample(to_dt(p), Some(worst_eval)));
+
+        let reflected = eval_bounded(along(1.0));
+        if reflected.1 < best_eval {
+            let expanded = eval_bounded(along(2.0));
+            simplex[n_dims] = if expanded.1 < reflected.1 { expanded } else { reflected };
+        } else if reflected.1 < second_worst_eval {
+            simplex[n_dims] = reflected;
+        } else {
+            let contracted = match reflected.1 < worst_eval {
+                true => eval_bounded(along(0.5)),
+                false => eval_bounded(along(-0.5)),
+            };
+            if contracted.1 < SampleEval::min(reflected.1, worst_eval) {
+                simplex[n_dims] = contracted;
+            } else {
+                //shrink all vertices towards the best one
+                for (p, eval) in simplex.iter_mut().skip(1) {
+                    *p = [0, 1, 2].map(|d| best[d] + 0.5 * (p[d] - best[d]));
+                    *eval = evaluator.evaluate_sample(to_dt(*p), None);
+                }
+            }
+        }
+    }
+
+    let (best, best_eval) = simplex.into_iter().min_by_key(|(_, eval)| *eval).unwrap();
+    let best_dt = to_dt(best);
+    trace!("NM: {} evals, {} -> {}, eval: {:?}", evaluator.n_evals() - n_evals_init, init_dt, best_dt, best_eval);
+    (best_dt, best_eval)
+}
@@ -0,0 +1,63 @@
+use crate::consts::{CD_STEP_FAIL, CD_STEP_SUCCESS};
+use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
+use crate::sample::coord_descent::CDConfig;
+use crate::sample::refiner::displace;
+use jagua_rs::geometry::DTransformation;
+use log::trace;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+/// Refines an initial 'sample' (transformation and evaluation) into a local minimum using a pattern search.
+/// Unlike the coordinate descent, every iteration polls all neighbours at the current step sizes:
+/// horizontally, vertically, diagonally and, if the item can be wiggled, each of these combined with a rotation in either direction.
+/// The neighbours are polled in random order and the first improving one is moved to, after which the steps grow.
+/// If no neighbour improves, the steps shrink.
+pub fn refine_pattern_search(
+    (init_dt, init_eval): (DTransformation, SampleEval),
+    evaluator: &mut impl SampleEvaluator,
+    config: CDConfig,
+    rng: &mut impl Rng,
+) -> (DTransformation, SampleEval) {
+    let n_evals_init = evaluator.n_evals();
+    let mut directions = poll_directions(config.wiggle);
+
+    let (mut pos, mut eval) = (init_dt, init_eval);
+    let (mut t_step, mut r_step) = (config.t_step_init, config.r_step_init);
+
+    while t_step >= config.t_step_limit || (config.wiggle && r_step >= config.r_step_limit) {
+        directions.shuffle(rng);
+        let improvement = directions.iter().find_map(|&dir| {
+            let c = displace(pos, dir, (t_step, r_step), &config);
+            let c_eval = evaluator.evaluate_sample(c, Some(eval));
+            (c_eval < eval).then_some((c, c_eval))
+        });
+
+        let m = match improvement {
+            Some(better) => {
+                (pos, eval) = better;
+                CD_STEP_SUCCESS
+            }
+            None => CD_STEP_FAIL,
+        };
+        t_step *= m;
+        r_step *= m;
+    }
+    trace!("PS: {} evals, {} -> {}, eval: {:?}", evaluator.n_evals() - n_evals_init, init_dt, pos, eval);
+    (pos, eval)
+}
+
+/// All non-zero directions (x, y, r) with components in {-1, 0, 1}, rotation only included if the item can be wiggled
+fn poll_directions(wiggle: bool) -> Vec<(f64, f64, f64)> {
+    let r_range: &[f64] = if wiggle { &[-1.0, 0.0, 1.0] } else { &[0.0] };
+    let mut directions = vec![];
+    for &dx in &[-1.0, 0.0, 1.0] {
+        for &dy in &[-1.0, 0.0, 1.0] {
+            for &dr in r_range {
+                if (dx, dy, dr) != (0.0, 0.0, 0.0) {
+                    directions.push((dx, dy, dr));
+                }
+            }
+        }
+    }
+    directions
+}
@@ -0,0 +1,56 @@
+use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
+use crate::sample::coord_descent::{refine_coord_desc, CDConfig};
+use crate::sample::nelder_mead::refine_nelder_mead;
+use crate::sample::pattern_search::refine_pattern_search;
+use crate::sample::slide::refine_slide;
+use jagua_rs::geometry::DTransformation;
+use rand::Rng;
+
+/// Local refinement of a sample (transformation and evaluation).
+/// The step sizes of the refinement are defined by a [`CDConfig`], regardless of the algorithm.
+pub trait Refiner {
+    fn refine(
+        &self,
+        sample: (DTransformation, SampleEval),
+        evaluator: &mut impl SampleEvaluator,
+        config: CDConfig,
+        rng: &mut impl Rng,
+    ) -> (DTransformation, SampleEval);
+}
+
+/// Refinement algorithms which can be selected for the pre-refine and final-refine steps of [`search_placement`](crate::sample::search::search_placement)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefineStrategy {
+    /// Coordinate descent along a random axis at a time, see [`refine_coord_desc`]
+    CoordinateDescent,
+    /// Pattern search polling all horizontal, vertical, diagonal and rotational neighbours, see [`refine_pattern_search`]
+    PatternSearch,
+    /// Nelder-Mead simplex over (x, y, r), see [`refine_nelder_mead`]
+    NelderMead,
+    /// Slides the item along the most promising direction until it makes contact, see [`refine_slide`]
+    SlideToContact,
+}
+
+impl Refiner for RefineStrategy {
+    fn refine(
+        &self,
+        sample: (DTransformation, SampleEval),
+        evaluator: &mut impl SampleEvaluator,
+        config: CDConfig,
+        rng: &mut impl Rng,
+    ) -> (DTransformation, SampleEval) {
+        match self {
+            RefineStrategy::CoordinateDescent => refine_coord_desc(sample, evaluator, config, rng),
+            RefineStrategy::PatternSearch => refine_pattern_search(sample, evaluator, config, rng),
+            RefineStrategy::NelderMead => refine_nelder_mead(sample, evaluator, config),
+            RefineStrategy::SlideToContact => refine_slide(sample, evaluator, config),
+        }
+    }
+}
+
+/// Displaces a transformation along a direction (x, y, r), scaled by the translation and rotation step sizes
+pub(crate) fn displace(dt: DTransformation, (dx, dy, dr): (f64, f64, f64), (t_step, r_step): (f64, f64), config: &CDConfig) -> DTransformation {
+    let (tx, ty) = dt.translation();
+    let r = config.clamp_rotation(dt.rotation() + dr * r_step);
+    DTransformation::new(r, (tx + dx * t_step, ty + dy * t_step))
+}
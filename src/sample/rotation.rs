@@ -0,0 +1,166 @@
+use itertools::Itertools;
+use jagua_rs::entities::Item;
+use jagua_rs::geometry::geo_enums::RotationRange;
+use ndarray::Array;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Configuration of how the rotations of an item are sampled and refined
+#[derive(Debug, Clone, Copy)]
+pub struct RotationConfig {
+    /// Number of rotations sampled for items with continuous rotation
+    pub n_samples: usize,
+    /// Initial and limit step size for the rotation in the first refinement
+    pub pre_refine_steps: (f64, f64),
+    /// Initial and limit step size for the rotation in the second (final) refinement
+    pub final_refine_steps: (f64, f64),
+    /// Learns a distribution over the rotations of accepted placements, disabled if `None`
+    pub learning: Option<RotationLearningConfig>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RotationLearningConfig {
+    /// Number of equally sized bins the full circle is divided into
+    pub n_bins: usize,
+    /// Ratio of the rotations which are still sampled uniformly, to keep exploring other orientations
+    pub uniform_ratio: f64,
+}
+
+/// Rotation sampling configuration of every item, together with the distribution over rotations learned from accepted placements.
+/// Learning only applies to items with continuous rotation.
+#[derive(Debug, Clone)]
+pub struct RotationModel {
+    default_config: RotationConfig,
+    /// Configurations of single items which override the default one
+    item_configs: Vec<Option<RotationConfig>>,
+    histograms: Vec<Option<Vec<usize>>>,
+}
+
+impl RotationModel {
+    pub fn new(n_items: usize, config: RotationConfig) -> Self {
+        Self {
+            default_config: config,
+            item_configs: vec![None; n_items],
+            histograms: vec![config.learning.map(|l| vec![0; l.n_bins]); n_items],
+        }
+    }
+
+    /// Overrides the configuration of a single item, resetting its learned distribution
+    pub fn set_item_config(&mut self, item_id: usize, config: RotationConfig) {
+        self.item_configs[item_id] = Some(config);
+        self.histograms[item_id] = config.learning.map(|l| vec![0; l.n_bins]);
+    }
+
+    pub fn config(&self, item_id: usize) -> &RotationConfig {
+        self.item_configs[item_id].as_ref().unwrap_or(&self.default_config)
+    }
+
+    /// Histogram of the rotations of accepted placements, if the item learns its rotation distribution
+    pub fn histogram(&self, item_id: usize) -> Option<&[usize]> {
+        self.histograms[item_id].as_deref()
+    }
+
+    /// Samples a set of rotations for an item.
+    /// For continuous rotation, these are evenly spaced unless a distribution is learned.
+    /// For rotation intervals, they are evenly spaced over all intervals.
+    pub fn sample_rotations(&self, item: &Item, rng: &mut impl Rng) -> Vec<f64> {
+        let config = self.config(item.id);
+        match &item.allowed_rotation {
+            RotationRange::None => vec![0.0],
+            RotationRange::Discrete(r) => r.clone(),
+            RotationRange::Intervals(intervals) => {
+                //spaced evenly over the total length of all intervals
+                let total_length = intervals.iter().map(|(start, end)| end - start).sum::<f64>();
+                Array::linspace(0.0, total_length, config.n_samples).iter()
+                    .map(|&t| position_in_intervals(t, intervals))
+                    .collect()
+            }
+            RotationRange::Continuous => match &self.histograms[item.id] {
+                None => Array::linspace(0.0, 2.0 * PI, config.n_samples).to_vec(),
+                Some(histogram) => {
+                    let uniform_ratio = config.learning.map_or(1.0, |l| l.uniform_ratio);
+                    //add-one smoothing, so no bin is ever excluded
+                    let distr = WeightedIndex::new(histogram.iter().map(|&c| c + 1)).unwrap();
+                    let bin_width = 2.0 * PI / histogram.len() as f64;
+                    (0..config.n_samples)
+                        .map(|_| match rng.random::<f64>() < uniform_ratio {
+                            true => rng.random_range(0.0..2.0 * PI),
+                            false => (distr.sample(rng) as f64 + rng.random::<f64>()) * bin_width,
+                        })
+                        .collect()
+                }
+            },
+        }
+    }
+
+    /// Registers the rotation of an accepted placement
+    pub fn record(&mut self, item_id: usize, rotation: f64) {
+        if let Some(histogram) = self.histograms[item_id].as_mut() {
+            let n_bins = histogram.len();
+            let r = rotation.rem_euclid(2.0 * PI);
+            let bin = ((r / (2.0 * PI)) * n_bins as f64) as usize;
+            histogram[bin.min(n_bins - 1)] += 1;
+        }
+    }
+
+    /// Takes over the configurations of single items and the learned distributions of another model (e.g. of a previous phase).
+    /// A distribution is only taken over if both models divide the circle into the same number of bins for the item.
+    pub fn adopt(&mut self, other: &RotationModel) {
+        for item_id in 0..self.histograms.len() {
+            if let Some(config) = other.item_configs[item_id] {
+                self.set_item_config(item_id, config);
+            }
+            if let (Some(hist), Some(other_hist)) = (self.histograms[item_id].as_mut(), other.histograms[item_id].as_ref())
+                && hist.len() == other_hist.len() {
+                hist.clone_from(other_hist);
+            }
+        }
+    }
+
+    /// All learned distributions with at least one recorded rotation, to include in the output of a run
+    pub fn learned_histograms(&self) -> Vec<RotationHistogram> {
+        self.histograms.iter().enumerate()
+            .filter_map(|(item_id, hist)| hist.as_ref().map(|h| (item_id, h)))
+            .filter(|(_, hist)| hist.iter().any(|&c| c > 0))
+            .map(|(item_id, hist)| RotationHistogram {
+                item_id,
+                bin_width: 360.0 / hist.len() as f64,
+                counts: hist.clone(),
+            })
+            .collect()
+    }
+
+    /// Human-readable summary of a learned distribution: the share of accepted placements per bin (in degrees)
+    pub fn format_histogram(&self, item_id: usize) -> Option<String> {
+        let histogram = self.histogram(item_id)?;
+        let total = histogram.iter().sum::<usize>().max(1);
+        let bin_width = 360.0 / histogram.len() as f64;
+        Some(histogram.iter().enumerate()
+            .map(|(i, &c)| format!("{:.0}°: {:.1}%", i as f64 * bin_width, 100.0 * c as f64 / total as f64))
+            .join(", "))
+    }
+}
+
+/// Distribution over the rotations of an item learned during a run, as included in the output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotationHistogram {
+    pub item_id: usize,
+    /// Width of every bin (in degrees), the first bin starts at 0°
+    pub bin_width: f64,
+    /// Number of accepted placements with a rotation in every bin
+    pub counts: Vec<usize>,
+}
+
+/// Maps a position along the concatenated intervals to the corresponding rotation
+fn position_in_intervals(mut t: f64, intervals: &[(f64, f64)]) -> f64 {
+    for &(start, end) in intervals {
+        if t <= end - start {
+            return start + t;
+        }
+        t -= end - start;
+    }
+    intervals.last().map_or(0.0, |(_, end)| *end)
+}
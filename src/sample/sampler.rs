@@ -0,0 +1,51 @@
+use jagua_rs::geometry::DTransformation;
+use rand::Rng;
+
+/// Generates candidate transformations for an item
+pub trait Sampler {
+    fn sample(&mut self, rng: &mut impl Rng) -> DTransformation;
+
+    /// Called when a drawn sample improved on the best sample found so far
+    fn notify_improvement(&mut self, _dt: DTransformation) {}
+}
+
+/// Shares of the container samples drawn by each type of sampler.
+/// The shares are relative to each other and do not need to sum to one.
+#[derive(Debug, Clone, Copy)]
+pub struct ContainerSamplerMix {
+    /// Uniform samples over the container, see [`UniformBBoxSampler`](crate::sample::uniform_sampler::UniformBBoxSampler)
+    pub uniform: f64,
+    /// Samples from a randomly shifted Halton sequence, see [`HaltonSequence`](crate::sample::low_discrepancy::HaltonSequence)
+    pub halton: f64,
+    /// Samples from a randomly shifted Sobol sequence, see [`SobolSequence`](crate::sample::low_discrepancy::SobolSequence)
+    pub sobol: f64,
+    /// Samples biased towards empty quadtree cells, see [`FreeSpaceSampler`](crate::sample::free_space_sampler::FreeSpaceSampler)
+    pub free_space: f64,
+}
+
+impl ContainerSamplerMix {
+    pub const UNIFORM: Self = Self {
+        uniform: 1.0,
+        halton: 0.0,
+        sobol: 0.0,
+        free_space: 0.0,
+    };
+
+    /// Divides `n_samples` over the samplers (uniform, halton, sobol, free space) according to their shares
+    pub fn split(&self, n_samples: usize) -> [usize; 4] {
+        let shares = [self.uniform, self.halton, self.sobol, self.free_space];
+        let total = shares.iter().sum::<f64>();
+        assert!(total > 0.0, "at least one container sampler should have a positive share");
+
+        //round the cumulative shares, so the samples always add up to n_samples
+        let mut split = [0; 4];
+        let (mut cumulative, mut assigned) = (0.0, 0);
+        for (n, share) in split.iter_mut().zip(shares) {
+            cumulative += share / total;
+            let cumulative_samples = (cumulative * n_samples as f64).round() as usize;
+            *n = cumulative_samples.min(n_samples) - assigned;
+            assigned += *n;
+        }
+        split
+    }
+}
@@ -1,10 +1,17 @@
 use jagua_rs::entities::{Item, Layout, PItemKey};
 use jagua_rs::geometry::DTransformation;
 use jagua_rs::geometry::geo_enums::RotationRange;
-use crate::consts::{SND_REFINE_CD_TL_RATIOS, PRE_REFINE_CD_TL_RATIOS, UNIQUE_SAMPLE_THRESHOLD, PRE_REFINE_CD_R_STEPS, SND_REFINE_CD_R_STEPS};
+use crate::consts::{SND_REFINE_CD_TL_RATIOS, PRE_REFINE_CD_TL_RATIOS, UNIQUE_SAMPLE_THRESHOLD};
 use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
 use crate::sample::best_samples::BestSamples;
-use crate::sample::coord_descent::{refine_coord_desc, CDConfig};
+use crate::sample::budget::AdaptiveBudgetConfig;
+use crate::sample::coord_descent::CDConfig;
+use crate::sample::refiner::{RefineStrategy, Refiner};
+use crate::sample::rotation::{RotationConfig, RotationModel};
+use crate::sample::free_space_sampler::FreeSpaceSampler;
+use crate::sample::ls_sampler::LSSampler;
+use crate::sample::low_discrepancy::{HaltonSequence, LowDiscrepancySampler, SobolSequence};
+use crate::sample::sampler::{ContainerSamplerMix, Sampler};
 use crate::sample::uniform_sampler::UniformBBoxSampler;
 use log::debug;
 use rand::Rng;
@@ -14,54 +21,121 @@ pub struct SampleConfig {
     pub n_container_samples: usize,
     pub n_focussed_samples: usize,
     pub n_coord_descents: usize,
+    /// Rotation sampling of all items, can be overridden per item in the [`RotationModel`]
+    pub rotation: RotationConfig,
+    /// Division of the container samples over the different samplers
+    pub container_sampler_mix: ContainerSamplerMix,
+    /// Sampler used for the focussed samples around the item's current placement
+    pub focussed_sampler: FocussedSampler,
+    /// Refinement of the best samples
+    pub pre_refiner: RefineStrategy,
+    /// Refinement of the single best sample after the pre-refinement
+    pub final_refiner: RefineStrategy,
+    /// Adapts the number of samples and coordinate descents per item, disabled if `None` (see [`SampleBudgets`](crate::sample::budget::SampleBudgets))
+    pub adaptive_budget: Option<AdaptiveBudgetConfig>,
+}
+
+/// Origin of a sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSource {
+    /// The item's current placement
+    Reference,
+    Focussed,
+    Container,
+}
+
+/// Statistics of a single [`search_placement`] call
+#[derive(Debug, Clone, Copy)]
+pub struct SearchStats {
+    pub n_evals: usize,
+    /// Origin of the best sample before the coordinate descents, `None` if no valid sample was found
+    pub best_source: Option<SampleSource>,
+    /// Relative reduction of the loss achieved by the coordinate descents, in [0, 1]
+    pub cd_gain: f64,
+}
+
+/// Strategy to sample in the vicinity of an item's current placement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocussedSampler {
+    /// Uniform samples within the bounding box of the item's current placement
+    UniformBBox,
+    /// Normally distributed samples around the best transformation found so far, see [`LSSampler`].
+    /// The standard deviations (initial, final) of the translation are relative to the largest dimension of the container,
+    /// those of the rotation are in radians.
+    LocalSearch { sd_transl: (f64, f64), sd_rot: (f64, f64) },
 }
 
 /// Algorithm 6 and Figure 7 from https://doi.org/10.48550/arXiv.2509.13329
-pub fn search_placement(l: &Layout, item: &Item, ref_pk: Option<PItemKey>, mut evaluator: impl SampleEvaluator, sample_config: SampleConfig, rng: &mut impl Rng) -> (Option<(DTransformation, SampleEval)>, usize) {
+pub fn search_placement(l: &Layout, item: &Item, ref_pk: Option<PItemKey>, mut evaluator: impl SampleEvaluator, sample_config: SampleConfig, rot_model: &RotationModel, rng: &mut impl Rng) -> (Option<(DTransformation, SampleEval)>, SearchStats) {
     let item_min_dim = f64::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
 
     let mut best_samples = BestSamples::new(sample_config.n_coord_descents, item_min_dim * UNIQUE_SAMPLE_THRESHOLD);
+    let rot_config = rot_model.config(item.id);
+    let rotations = rot_model.sample_rotations(item, rng);
+    let mut best_source = None;
 
-    let focussed_sampler = match ref_pk {
-        Some(ref_pk) => {
-            //report the current placement (and eval)
-            let dt = l.placed_items[ref_pk].d_transf;
-            let eval = evaluator.evaluate_sample(dt, Some(best_samples.upper_bound()));
+    if let Some(ref_pk) = ref_pk {
+        //report the current placement (and eval)
+        let dt = l.placed_items[ref_pk].d_transf;
+        let eval = evaluator.evaluate_sample(dt, Some(best_samples.upper_bound()));
 
-            debug!("[S] Starting from: {:?}", (dt, eval));
-            best_samples.report(dt, eval);
+        debug!("[S] Starting from: {:?}", (dt, eval));
+        best_samples.report(dt, eval);
+        best_source = Some(SampleSource::Reference);
 
-            //create a sampler around the current placement
-            let pi_bbox = l.placed_items[ref_pk].shape.bbox;
-            UniformBBoxSampler::new(pi_bbox, item, l.container.outer_cd.bbox)
-        }
-        None => None,
-    };
+        let best_before = best_samples.best();
 
-    if let Some(focussed_sampler) = focussed_sampler {
-        for _ in 0..sample_config.n_focussed_samples {
-            let dt = focussed_sampler.sample(rng);
-            let eval = evaluator.evaluate_sample(dt, Some(best_samples.upper_bound()));
-            best_samples.report(dt, eval);
+        match sample_config.focussed_sampler {
+            FocussedSampler::UniformBBox => {
+                //create a sampler around the current placement
+                let pi_bbox = l.placed_items[ref_pk].shape.bbox;
+                if let Some(mut focussed_sampler) = UniformBBoxSampler::new(pi_bbox, item, l.container.outer_cd.bbox, &rotations) {
+                    collect_samples(&mut focussed_sampler, sample_config.n_focussed_samples, &mut evaluator, &mut best_samples, rng);
+                }
+            }
+            FocussedSampler::LocalSearch { sd_transl, sd_rot } => {
+                let cont_bbox = l.container.outer_cd.bbox;
+                let max_dim = f64::max(cont_bbox.width(), cont_bbox.height());
+                let sd_transl_range = (sd_transl.0 * max_dim, sd_transl.1 * max_dim);
+                let mut ls_sampler = LSSampler::new(item, dt, sd_transl_range, sd_rot, sample_config.n_focussed_samples);
+                collect_samples(&mut ls_sampler, sample_config.n_focussed_samples, &mut evaluator, &mut best_samples, rng);
+            }
+        }
+        if improved_on(&best_samples, best_before) {
+            best_source = Some(SampleSource::Focussed);
         }
     }
 
-    let container_sampler = UniformBBoxSampler::new(l.container.outer_cd.bbox, item, l.container.outer_cd.bbox);
+    let container_sampler = UniformBBoxSampler::new(l.container.outer_cd.bbox, item, l.container.outer_cd.bbox, &rotations);
 
-    if let Some(container_sampler) = container_sampler {
-        for _ in 0..sample_config.n_container_samples {
-            let dt = container_sampler.sample(rng).into();
-            let eval = evaluator.evaluate_sample(dt, Some(best_samples.upper_bound()));
-            best_samples.report(dt, eval);
+    if let Some(mut container_sampler) = container_sampler {
+        let best_before = best_samples.best();
+        let [n_uniform, n_halton, n_sobol, n_free_space] = sample_config.container_sampler_mix.split(sample_config.n_container_samples);
+        if n_halton > 0 {
+            let mut halton_sampler = LowDiscrepancySampler::new(&container_sampler, HaltonSequence::new(rng));
+            collect_samples(&mut halton_sampler, n_halton, &mut evaluator, &mut best_samples, rng);
+        }
+        if n_sobol > 0 {
+            let mut sobol_sampler = LowDiscrepancySampler::new(&container_sampler, SobolSequence::new(rng));
+            collect_samples(&mut sobol_sampler, n_sobol, &mut evaluator, &mut best_samples, rng);
+        }
+        if n_free_space > 0 {
+            let mut free_space_sampler = FreeSpaceSampler::new(&container_sampler, l.cde());
+            collect_samples(&mut free_space_sampler, n_free_space, &mut evaluator, &mut best_samples, rng);
+        }
+        collect_samples(&mut container_sampler, n_uniform, &mut evaluator, &mut best_samples, rng);
+        if improved_on(&best_samples, best_before) {
+            best_source = Some(SampleSource::Container);
         }
     }
+    let best_sampled = best_samples.best();
     
     //Prerefine the best samples
     for start in best_samples.samples.clone() {
-        let descended = refine_coord_desc(
+        let descended = sample_config.pre_refiner.refine(
             start.clone(),
             &mut evaluator,
-            prerefine_cd_config(item),
+            prerefine_cd_config(item, rot_config),
             rng,
         );
         best_samples.report(descended.0, descended.1);
@@ -70,38 +144,87 @@ pub fn search_placement(l: &Layout, item: &Item, ref_pk: Option<PItemKey>, mut e
 
     //Do a final refine on the best one
     let final_sample = best_samples.best().map(|s|
-        refine_coord_desc(
+        sample_config.final_refiner.refine(
             s, 
             &mut evaluator, 
-            final_refine_cd_config(item), 
+            final_refine_cd_config(item, rot_config), 
             rng,
         )
     );
 
     debug!("[S] {} samples evaluated, final: {:?}",evaluator.n_evals(),final_sample);
-    (final_sample, evaluator.n_evals())
+    let stats = SearchStats {
+        n_evals: evaluator.n_evals(),
+        best_source: best_source.filter(|_| best_sampled.is_some_and(|(_, e)| e != SampleEval::Invalid)),
+        cd_gain: match (best_sampled, final_sample) {
+            (Some((_, pre)), Some((_, post))) => relative_gain(pre, post),
+            _ => 0.0,
+        },
+    };
+    (final_sample, stats)
 }
 
-fn prerefine_cd_config(item: &Item) -> CDConfig {
+fn improved_on(best_samples: &BestSamples, best_before: Option<(DTransformation, SampleEval)>) -> bool {
+    match (best_samples.best(), best_before) {
+        (Some((_, after)), Some((_, before))) => after < before,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Relative reduction of the loss between two evaluations of a sample, resolving a collision counts as a full reduction
+fn relative_gain(before: SampleEval, after: SampleEval) -> f64 {
+    match (before, after) {
+        (SampleEval::Collision { loss: l0 }, SampleEval::Collision { loss: l1 }) if l0 > 0.0 => ((l0 - l1) / l0).clamp(0.0, 1.0),
+        (SampleEval::Collision { .. }, SampleEval::Clear { .. }) => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Draws `n_samples` from the sampler, evaluates them and reports them to `best_samples`
+fn collect_samples(sampler: &mut impl Sampler, n_samples: usize, evaluator: &mut impl SampleEvaluator, best_samples: &mut BestSamples, rng: &mut impl Rng) {
+    for _ in 0..n_samples {
+        let dt = sampler.sample(rng);
+        let eval = evaluator.evaluate_sample(dt, Some(best_samples.upper_bound()));
+        let improved = best_samples.best().is_none_or(|(_, best_eval)| eval < best_eval);
+        best_samples.report(dt, eval);
+        if improved {
+            sampler.notify_improvement(dt);
+        }
+    }
+}
+
+pub fn prerefine_cd_config<'a>(item: &'a Item, rot_config: &RotationConfig) -> CDConfig<'a> {
     let item_min_dim = f64::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
-    let wiggle = item.allowed_rotation == RotationRange::Continuous;
+    let (wiggle, r_intervals) = wiggle_axis(item);
     CDConfig {
         t_step_init: item_min_dim * PRE_REFINE_CD_TL_RATIOS.0,
         t_step_limit: item_min_dim * PRE_REFINE_CD_TL_RATIOS.1,
-        r_step_init: PRE_REFINE_CD_R_STEPS.0,
-        r_step_limit: PRE_REFINE_CD_R_STEPS.1,
+        r_step_init: rot_config.pre_refine_steps.0,
+        r_step_limit: rot_config.pre_refine_steps.1,
         wiggle,
+        r_intervals,
     }
 }
 
-fn final_refine_cd_config(item: &Item) -> CDConfig {
+fn final_refine_cd_config<'a>(item: &'a Item, rot_config: &RotationConfig) -> CDConfig<'a> {
     let item_min_dim = f64::min(item.shape_cd.bbox.width(), item.shape_cd.bbox.height());
-    let wiggle = item.allowed_rotation == RotationRange::Continuous;
+    let (wiggle, r_intervals) = wiggle_axis(item);
     CDConfig {
         t_step_init: item_min_dim * SND_REFINE_CD_TL_RATIOS.0,
         t_step_limit: item_min_dim * SND_REFINE_CD_TL_RATIOS.1,
-        r_step_init: SND_REFINE_CD_R_STEPS.0,
-        r_step_limit: SND_REFINE_CD_R_STEPS.1,
-        wiggle
+        r_step_init: rot_config.final_refine_steps.0,
+        r_step_limit: rot_config.final_refine_steps.1,
+        wiggle,
+        r_intervals,
+    }
+}
+
+/// Whether the item can be wiggled during coordinate descent, and the intervals its rotation is clamped to
+fn wiggle_axis(item: &Item) -> (bool, Option<&[(f64, f64)]>) {
+    match &item.allowed_rotation {
+        RotationRange::Continuous => (true, None),
+        RotationRange::Intervals(intervals) => (true, Some(intervals)),
+        RotationRange::None | RotationRange::Discrete(_) => (false, None),
     }
 }
@@ -0,0 +1,67 @@
+use crate::consts::CD_STEP_FAIL;
+use crate::eval::sample_eval::{SampleEval, SampleEvaluator};
+use crate::sample::coord_descent::CDConfig;
+use crate::sample::refiner::displace;
+use jagua_rs::geometry::DTransformation;
+use log::trace;
+
+/// Horizontal, vertical and diagonal directions
+const TRANSLATIONS: [(f64, f64, f64); 8] = [
+    (1.0, 0.0, 0.0), (-1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, -1.0, 0.0),
+    (1.0, 1.0, 0.0), (-1.0, -1.0, 0.0), (-1.0, 1.0, 0.0), (1.0, -1.0, 0.0),
+];
+
+/// Rotations in either direction
+const ROTATIONS: [(f64, f64, f64); 2] = [(0.0, 0.0, 1.0), (0.0, 0.0, -1.0)];
+
+/// Refines an initial 'sample' (transformation and evaluation) by sliding it until contact.
+/// All horizontal, vertical and diagonal neighbours (and rotations, if the item can be wiggled) at the current step sizes are probed
+/// and the item is moved in the best improving direction. It then keeps sliding along this direction, doubling the distance of every move,
+/// until a move no longer improves, i.e. until the item makes contact. If no direction improves, the steps shrink.
+pub fn refine_slide(
+    (init_dt, init_eval): (DTransformation, SampleEval),
+    evaluator: &mut impl SampleEvaluator,
+    config: CDConfig,
+) -> (DTransformation, SampleEval) {
+    let n_evals_init = evaluator.n_evals();
+    let directions = match config.wiggle {
+        true => [&TRANSLATIONS[..], &ROTATIONS[..]].concat(),
+        false => TRANSLATIONS.to_vec(),
+    };
+
+    let (mut pos, mut eval) = (init_dt, init_eval);
+    let (mut t_step, mut r_step) = (config.t_step_init, config.r_step_init);
+
+    while t_step >= config.t_step_limit || (config.wiggle && r_step >= config.r_step_limit) {
+        let best_probe = directions.iter()
+            .map(|&dir| {
+                let c = displace(pos, dir, (t_step, r_step), &config);
+                (dir, c, evaluator.evaluate_sample(c, Some(eval)))
+            })
+            .min_by_key(|(_, _, c_eval)| *c_eval)
+            .filter(|(_, _, c_eval)| *c_eval < eval);
+
+        match best_probe {
+            Some((dir, c, c_eval)) => {
+                (pos, eval) = (c, c_eval);
+                //slide along the direction until contact
+                let mut dist = 2.0;
+                loop {
+                    let c = displace(pos, dir, (t_step * dist, r_step * dist), &config);
+                    let c_eval = evaluator.evaluate_sample(c, Some(eval));
+                    if c_eval >= eval {
+                        break;
+                    }
+                    (pos, eval) = (c, c_eval);
+                    dist *= 2.0;
+                }
+            }
+            None => {
+                t_step *= CD_STEP_FAIL;
+                r_step *= CD_STEP_FAIL;
+            }
+        }
+    }
+    trace!("SL: {} evals, {} -> {}, eval: {:?}", evaluator.n_evals() - n_evals_init, init_dt, pos, eval);
+    (pos, eval)
+}
@@ -1,4 +1,3 @@
-use std::f64::consts::PI;
 use itertools::Itertools;
 use jagua_rs::geometry::geo_enums::RotationRange;
 use jagua_rs::geometry::geo_traits::TransformableFrom;
@@ -7,11 +6,9 @@ use rand::Rng;
 use std::ops::Range;
 use jagua_rs::entities::Item;
 use jagua_rs::geometry::primitives::Rect;
-use jagua_rs::geometry::{normalize_rotation, DTransformation, Transformation};
-use ndarray::Array;
+use jagua_rs::geometry::{closest_rotation_in_intervals, normalize_rotation, DTransformation, Transformation};
 use ordered_float::{OrderedFloat};
-
-const ROT_N_SAMPLES: usize = 16; // number of rotations to sample for continuous rotation
+use crate::sample::sampler::Sampler;
 
 /// A sampler that creates uniform samples for an item within a bounding box
 #[derive(Clone, Debug)]
@@ -28,16 +25,8 @@ struct RotEntry {
 }
 
 impl UniformBBoxSampler {
-    pub fn new(sample_bbox: Rect, item: &Item, container_bbox: Rect) -> Option<Self> {
-        let rotations = match &item.allowed_rotation {
-            RotationRange::None => &vec![0.0],
-            RotationRange::Discrete(r) => r,
-            RotationRange::Continuous => {
-                // for continuous rotation, we sample a set of rotations spaced evenly
-                &Array::linspace(0.0, 2.0 * PI, ROT_N_SAMPLES).to_vec()
-            }
-        };
-
+    /// Creates a sampler for the given set of rotations (see [`RotationModel::sample_rotations`](crate::sample::rotation::RotationModel::sample_rotations))
+    pub fn new(sample_bbox: Rect, item: &Item, container_bbox: Rect, rotations: &[f64]) -> Option<Self> {
         let mut shape_buffer = item.shape_cd.as_ref().clone();
 
         let sample_x_range = sample_bbox.x_min..sample_bbox.x_max;
@@ -82,6 +71,37 @@ impl UniformBBoxSampler {
 
         DTransformation::new(r, (x_sample, y_sample))
     }
+
+    /// Maps a point of the unit cube to a sample: the first two coordinates determine the position, the third one the rotation
+    pub fn sample_from_unit(&self, [ux, uy, ur]: [f64; 3]) -> DTransformation {
+        let r_idx = ((ur * self.rot_entries.len() as f64) as usize).min(self.rot_entries.len() - 1);
+        let r_entry = &self.rot_entries[r_idx];
+
+        let x_sample = r_entry.x_range.start + ux * (r_entry.x_range.end - r_entry.x_range.start);
+        let y_sample = r_entry.y_range.start + uy * (r_entry.y_range.end - r_entry.y_range.start);
+
+        DTransformation::new(r_entry.r, (x_sample, y_sample))
+    }
+
+    /// Samples a transformation for a random rotation, restricted to positions within `bbox`.
+    /// Returns `None` if no valid position lies within `bbox` for the selected rotation.
+    pub fn sample_within(&self, bbox: Rect, rng: &mut impl Rng) -> Option<DTransformation> {
+        let r_entry = self.rot_entries.choose(rng).unwrap();
+
+        let x_range = intersect_range(&r_entry.x_range, &(bbox.x_min..bbox.x_max));
+        let y_range = intersect_range(&r_entry.y_range, &(bbox.y_min..bbox.y_max));
+        if x_range.is_empty() || y_range.is_empty() {
+            return None;
+        }
+
+        Some(DTransformation::new(r_entry.r, (rng.random_range(x_range), rng.random_range(y_range))))
+    }
+}
+
+impl Sampler for UniformBBoxSampler {
+    fn sample(&mut self, rng: &mut impl Rng) -> DTransformation {
+        UniformBBoxSampler::sample(self, rng)
+    }
 }
 
 fn intersect_range(a: &Range<f64>, b: &Range<f64>) -> Range<f64> {
@@ -106,6 +126,10 @@ pub fn convert_sample_to_closest_feasible(dt: DTransformation, item: &Item) -> D
             // for continuous rotation, we can just use the sample rotation
             dt.rotation()
         }
+        RotationRange::Intervals(intervals) => {
+            // clamp the rotation to the closest allowed interval
+            closest_rotation_in_intervals(dt.rotation(), intervals)
+        }
     };
     DTransformation::new(feasible_rotation, dt.translation())
 }
\ No newline at end of file
@@ -113,7 +113,7 @@ pub fn tracker_matches_layout(ct: &CollisionTracker, l: &Layout) -> bool {
         }
         if collector.contains_entity(&HazardEntity::Exterior) {
             let stored_loss = ct.get_container_loss(pk1);
-            let calc_loss = quantify_collision_poly_container(&pi1.shape, l.container.outer_cd.bbox);
+            let calc_loss = quantify_collision_poly_container(&pi1.shape, &l.container.outer_cd);
             assert_approx_eq!(f64, stored_loss, calc_loss, ulps = 5);
         } else {
             assert_eq!(ct.get_container_loss(pk1), 0.0);
@@ -3,7 +3,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use jagua_rs::Instant;
 use log::warn;
-use crate::util::terminator::Terminator;
+use crate::util::terminator::{KillReason, Terminator};
 
 #[derive(Debug, Clone)]
 pub struct CtrlCTerminator {
@@ -44,4 +44,13 @@ impl Terminator for CtrlCTerminator {
     fn timeout_at(&self) -> Option<Instant> {
         self.timeout
     }
+
+    fn kill_reason(&self) -> Option<KillReason> {
+        match self.ctrlc.load(Ordering::SeqCst) {
+            true => Some(KillReason::Interrupted),
+            false => self.timeout
+                .filter(|timeout| Instant::now() > *timeout)
+                .map(|_| KillReason::Timeout),
+        }
+    }
 }
\ No newline at end of file
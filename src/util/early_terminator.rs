@@ -0,0 +1,179 @@
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use jagua_rs::Instant;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use crate::util::listener::{ReportType, SepProgress, SolutionListener};
+use crate::util::terminator::{KillReason, Terminator};
+
+/// Quality of a feasible solution at which the optimization process can stop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    /// Side of the container is at most this size
+    Size(f64),
+    /// Density of the solution is at least this ratio
+    Density(f64),
+    /// Score (side² / number of items) is at most this value
+    Score(f64),
+}
+
+impl Target {
+    pub fn is_reached(&self, solution: &SPSolution, instance: &SPInstance) -> bool {
+        let size = solution.strip_width();
+        match *self {
+            Target::Size(target) => size <= target,
+            Target::Density(target) => solution.density(instance) >= target,
+            Target::Score(target) => size * size / instance.total_item_qty() as f64 <= target,
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Size(size) => write!(f, "size <= {size}"),
+            Target::Density(density) => write!(f, "density >= {:.3}%", density * 100.0),
+            Target::Score(score) => write!(f, "score <= {score}"),
+        }
+    }
+}
+
+/// Limits on the time and number of attempts (separations) spent within a phase without finding an improving feasible solution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StagnationLimit {
+    pub time: Option<Duration>,
+    pub attempts: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EarlyTerminationConfig {
+    /// Stops the optimization process (all remaining phases) as soon as a feasible solution reaches the target
+    pub target: Option<Target>,
+    /// Stops the exploration phase when it stagnates
+    pub expl_stagnation: StagnationLimit,
+    /// Stops the compression phase when it stagnates
+    pub cmpr_stagnation: StagnationLimit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Exploration,
+    Compression,
+}
+
+#[derive(Debug)]
+struct EarlyTerminationState {
+    phase: Option<Phase>,
+    target_reached: bool,
+    last_improvement: Instant,
+    n_attempts_no_improvement: usize,
+    best_size: f64,
+}
+
+/// Wraps another terminator (e.g. [`CtrlCTerminator`](crate::util::ctrlc_terminator::CtrlCTerminator)) and additionally terminates
+/// when a target is reached or when a phase stagnates, see [`EarlyTerminationConfig`].
+/// It follows the optimization process through the listener created by [`EarlyTerminator::listener`], which should receive all reports.
+///
+/// Every call to [`Terminator::new_timeout`] starts a new phase: exploration and compression are assumed to alternate, as in [`optimize`](crate::optimizer::optimize).
+/// A new exploration phase starts a new run, resetting the target.
+#[derive(Debug, Clone)]
+pub struct EarlyTerminator<T: Terminator> {
+    pub inner: T,
+    pub config: EarlyTerminationConfig,
+    state: Arc<Mutex<EarlyTerminationState>>,
+}
+
+impl<T: Terminator> EarlyTerminator<T> {
+    pub fn new(inner: T, config: EarlyTerminationConfig) -> Self {
+        let state = EarlyTerminationState {
+            phase: None,
+            target_reached: false,
+            last_improvement: Instant::now(),
+            n_attempts_no_improvement: 0,
+            best_size: f64::INFINITY,
+        };
+        Self { inner, config, state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Creates the listener which keeps track of the progress of the optimization process
+    pub fn listener(&self) -> EarlyTerminationListener {
+        EarlyTerminationListener {
+            target: self.config.target,
+            state: self.state.clone(),
+        }
+    }
+
+    fn early_kill_reason(&self) -> Option<KillReason> {
+        let state = self.state.lock().unwrap();
+        let stagnation = match state.phase? {
+            Phase::Exploration => self.config.expl_stagnation,
+            Phase::Compression => self.config.cmpr_stagnation,
+        };
+        if state.target_reached {
+            self.config.target.map(KillReason::TargetReached)
+        } else if let Some(limit) = stagnation.time && state.last_improvement.elapsed() > limit {
+            Some(KillReason::StagnationTime(limit))
+        } else if let Some(limit) = stagnation.attempts && state.n_attempts_no_improvement >= limit {
+            Some(KillReason::StagnationAttempts(limit))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Terminator> Terminator for EarlyTerminator<T> {
+    fn kill(&self) -> bool {
+        self.inner.kill() || self.early_kill_reason().is_some()
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        self.inner.new_timeout(timeout);
+        let mut state = self.state.lock().unwrap();
+        state.phase = match state.phase {
+            Some(Phase::Exploration) => Some(Phase::Compression),
+            _ => {
+                state.target_reached = false;
+                state.best_size = f64::INFINITY;
+                Some(Phase::Exploration)
+            }
+        };
+        state.last_improvement = Instant::now();
+        state.n_attempts_no_improvement = 0;
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        self.inner.timeout_at()
+    }
+
+    fn kill_reason(&self) -> Option<KillReason> {
+        self.inner.kill_reason().or_else(|| self.early_kill_reason())
+    }
+}
+
+/// Feeds the progress of the optimization process to an [`EarlyTerminator`]
+pub struct EarlyTerminationListener {
+    target: Option<Target>,
+    state: Arc<Mutex<EarlyTerminationState>>,
+}
+
+impl SolutionListener for EarlyTerminationListener {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        if matches!(report_type, ReportType::ExplFeas | ReportType::CmprFeas) {
+            let mut state = self.state.lock().unwrap();
+            if solution.strip_width() < state.best_size {
+                state.best_size = solution.strip_width();
+                state.last_improvement = Instant::now();
+                state.n_attempts_no_improvement = 0;
+            }
+            if let Some(target) = self.target && target.is_reached(solution, instance) {
+                state.target_reached = true;
+            }
+        }
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        if progress.finished {
+            self.state.lock().unwrap().n_attempts_no_improvement += 1;
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::util::listener::{ReportType, SepProgress, SolutionListener};
+use crate::EPOCH;
+use anyhow::{Context, Result};
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::warn;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Listener which writes every report as a single line of JSON (NDJSON), see [`ReportEvent`].
+/// One stream can be shared by consecutive jobs, see [`EventStreamListener::start_job`].
+pub struct EventStreamListener {
+    writer: Box<dyn Write + Send>,
+    /// Job to which the reports belong, if any
+    job: Option<String>,
+    /// Latest progress of the separator
+    progress: Option<SepProgress>,
+}
+
+/// Summary of a single report: a line of the event stream
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportEvent {
+    /// Job to which the report belongs, absent outside of a batch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+    pub report_type: String,
+    pub phase: &'static str,
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u128,
+    /// Seconds since the start of the process
+    pub elapsed_s: f64,
+    pub container_width: f64,
+    pub container_height: f64,
+    pub density: f64,
+    pub total_loss: f64,
+    /// Throughput of the latest separation, absent if no separation was reported yet
+    pub evals_per_sec: Option<f64>,
+    pub moves_per_sec: Option<f64>,
+    pub uphill_moves: Option<usize>,
+    pub swaps: Option<usize>,
+}
+
+impl ReportEvent {
+    /// Summarizes a report, with the throughput and loss of the latest progress of the separator
+    pub fn new(report_type: ReportType, solution: &SPSolution, instance: &SPInstance, progress: Option<&SepProgress>) -> Self {
+        let bbox = solution.layout_snapshot.container.outer_cd.bbox;
+        let total_loss = match report_type {
+            ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final => 0.0,
+            ReportType::ExplInfeas | ReportType::ExplImproving => progress.map_or(f64::NAN, |p| p.total_loss),
+        };
+        Self {
+            job: None,
+            report_type: format!("{report_type:?}"),
+            phase: report_type.phase(),
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis()),
+            elapsed_s: EPOCH.elapsed().as_secs_f64(),
+            container_width: bbox.width(),
+            container_height: bbox.height(),
+            density: solution.density(instance),
+            total_loss,
+            evals_per_sec: progress.map(|p| p.evals_per_sec()),
+            moves_per_sec: progress.map(|p| p.moves_per_sec()),
+            uphill_moves: progress.map(|p| p.stats.total_uphill_moves),
+            swaps: progress.map(|p| p.stats.total_swaps),
+        }
+    }
+}
+
+impl EventStreamListener {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer, job: None, progress: None }
+    }
+
+    /// Writes the events to a file, or to stdout if the path is `-` (the logger then writes to stderr, see [`crate::util::io::init_logger`]).
+    /// The file is truncated, so it should be opened only once per process.
+    pub fn open(path: &str) -> Result<Self> {
+        let writer: Box<dyn Write + Send> = match path {
+            "-" => Box::new(std::io::stdout()),
+            _ => {
+                let file = File::create(Path::new(path)).context(format!("could not create event stream file: {path}"))?;
+                Box::new(BufWriter::new(file))
+            }
+        };
+        Ok(Self::new(writer))
+    }
+
+    /// Tags all following events with the name of a job and forgets the progress of the previous one
+    pub fn start_job(&mut self, job: String) {
+        self.job = Some(job);
+        self.progress = None;
+    }
+
+    fn write_event(&mut self, event: &ReportEvent) -> Result<()> {
+        //the entire line is written at once, so it cannot be interleaved with other output
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        //flush every event, so the stream can be followed while the run is ongoing
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SolutionListener for EventStreamListener {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        let event = ReportEvent {
+            job: self.job.clone(),
+            ..ReportEvent::new(report_type, solution, instance, self.progress.as_ref())
+        };
+        if let Err(e) = self.write_event(&event) {
+            warn!("failed to write event: {e}");
+        }
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        self.progress = Some(*progress);
+    }
+}
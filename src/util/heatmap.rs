@@ -0,0 +1,102 @@
+use crate::quantify::tracker::CollisionTracker;
+use clap::ValueEnum;
+use itertools::Itertools;
+use jagua_rs::entities::LayoutSnapshot;
+use jagua_rs::geometry::primitives::SPolygon;
+use svg::node::element::path::Data;
+use svg::node::element::{Group, Line, Path};
+use svg::Document;
+
+/// Quantity of the [`CollisionTracker`] by which the items of a heatmap are colored
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum HeatmapMetric {
+    /// See [`CollisionTracker::get_loss`]
+    Loss,
+    /// See [`CollisionTracker::get_weighted_loss`]
+    WeightedLoss,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeatmapOptions {
+    pub metric: HeatmapMetric,
+    /// Draws a link between the centroids of every pair of items with a raised weight
+    pub pair_weight_links: bool,
+}
+
+/// Overlays a heatmap of the collision tracker on an SVG of the layout:
+/// - items are filled from yellow to red by their loss, relative to the item with the highest loss. Items without loss are not filled.
+/// - items with a raised container weight are outlined, from yellow to red by their weight relative to the highest one.
+/// - pairs of items with a raised weight are linked, from yellow to red by their weight relative to the highest one.
+pub fn add_heatmap(document: Document, layout: &LayoutSnapshot, ct: &CollisionTracker, options: HeatmapOptions) -> Document {
+    let bbox = layout.container.outer_cd.bbox;
+    let stroke_width = f64::min(bbox.width(), bbox.height()) * 0.002;
+
+    let item_loss = |pk| match options.metric {
+        HeatmapMetric::Loss => ct.get_loss(pk),
+        HeatmapMetric::WeightedLoss => ct.get_weighted_loss(pk),
+    };
+    let max_loss = layout.placed_items.keys().map(item_loss).fold(0.0, f64::max);
+    let max_container_weight = layout.placed_items.keys().map(|pk| ct.get_container_weight(pk)).fold(1.0, f64::max);
+
+    let mut items_group = Group::new().set("id", "heatmap_items");
+    for (pk, pi) in layout.placed_items.iter() {
+        let loss = item_loss(pk);
+        let container_weight = ct.get_container_weight(pk);
+        if loss == 0.0 && container_weight <= 1.0 {
+            continue;
+        }
+        let (fill, fill_opacity) = match loss > 0.0 {
+            true => (heat_color(loss / max_loss), 0.2 + 0.6 * loss / max_loss),
+            false => ("none".to_string(), 0.0),
+        };
+        let (stroke, stroke_w) = match container_weight > 1.0 {
+            true => (heat_color(container_weight / max_container_weight), 2.0 * stroke_width),
+            false => ("none".to_string(), 0.0),
+        };
+        items_group = items_group.add(Path::new()
+            .set("d", polygon_data(&pi.shape))
+            .set("fill", fill)
+            .set("fill-opacity", fill_opacity)
+            .set("stroke", stroke)
+            .set("stroke-width", stroke_w));
+    }
+
+    let mut links_group = Group::new().set("id", "heatmap_pair_weights");
+    if options.pair_weight_links {
+        let raised_pairs = layout.placed_items.keys()
+            .tuple_combinations()
+            .map(|(pk1, pk2)| (pk1, pk2, ct.get_pair_weight(pk1, pk2)))
+            .filter(|(_, _, w)| *w > 1.0)
+            .collect_vec();
+        let max_pair_weight = raised_pairs.iter().map(|(_, _, w)| *w).fold(1.0, f64::max);
+
+        //draw the highest weights last, on top of the others
+        for (pk1, pk2, w) in raised_pairs.into_iter().sorted_by(|a, b| a.2.total_cmp(&b.2)) {
+            let (c1, c2) = (layout.placed_items[pk1].shape.centroid(), layout.placed_items[pk2].shape.centroid());
+            let rel_w = w / max_pair_weight;
+            links_group = links_group.add(Line::new()
+                .set("x1", c1.0).set("y1", c1.1)
+                .set("x2", c2.0).set("y2", c2.1)
+                .set("stroke", heat_color(rel_w))
+                .set("stroke-width", stroke_width * (1.0 + 3.0 * rel_w))
+                .set("stroke-opacity", 0.8)
+                .set("stroke-linecap", "round"));
+        }
+    }
+
+    document.add(items_group).add(links_group)
+}
+
+/// Color from yellow (0.0) to red (1.0)
+fn heat_color(t: f64) -> String {
+    let g = (255.0 * (1.0 - t.clamp(0.0, 1.0))) as u8;
+    format!("#FF{g:02X}00")
+}
+
+fn polygon_data(polygon: &SPolygon) -> Data {
+    let mut data = Data::new().move_to::<(f64, f64)>(polygon.vertices[0].into());
+    for v in &polygon.vertices[1..] {
+        data = data.line_to::<(f64, f64)>((*v).into());
+    }
+    data.close()
+}
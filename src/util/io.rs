@@ -6,12 +6,21 @@ use std::path::Path;
 use std::io::Write;
 use serde::{Deserialize, Serialize};
 use svg::Document;
-use anyhow::{Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use clap::Parser;
+use jagua_rs::entities::{Instance, Layout};
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::io::ext_repr::{ExtPlacedItem, ExtTransformation};
+use jagua_rs::io::import::ext_to_int_transformation;
+use jagua_rs::probs::spp::entities::SPInstance;
 use jagua_rs::probs::spp::io::ext_repr::{ExtSPInstance, ExtSPSolution};
 use std::fs::OpenOptions;
 use crate::EPOCH;
+use crate::consts::{ANNEALING_T_DECAY, ANNEALING_T_INIT, GLS_WEIGHT_DECAY, GLS_WEIGHT_MAX_INC_RATIO, GLS_WEIGHT_MIN_INC_RATIO};
+use crate::sample::rotation::RotationHistogram;
+use crate::util::heatmap::HeatmapMetric;
 
+/// Arguments shared by the `solve` and `batch` subcommands
 #[derive(Parser)]
 pub struct MainCli {
     /// Path to input file (mandatory)
@@ -34,8 +43,110 @@ pub struct MainCli {
     #[arg(short = 'x', long, help = "Enable early termination of the optimization process")]
     pub early_termination: bool,
 
+    /// Stop as soon as a feasible solution with a container side of at most this size is found
+    #[arg(long, requires = "early_termination", conflicts_with_all = &["target_density", "target_score"], help = "Stop when the container side reaches this size (requires -x)")]
+    pub target_size: Option<f64>,
+
+    /// Stop as soon as a feasible solution with at least this density (0.0 - 1.0) is found
+    #[arg(long, requires = "early_termination", conflicts_with = "target_score", help = "Stop when the density reaches this ratio (requires -x)")]
+    pub target_density: Option<f64>,
+
+    /// Stop as soon as a feasible solution with a score (side² / number of items) of at most this value is found
+    #[arg(long, requires = "early_termination", help = "Stop when the score (side^2 / n) reaches this value (requires -x)")]
+    pub target_score: Option<f64>,
+
+    /// Stop a phase when it has not improved for this many seconds
+    #[arg(long, requires = "early_termination", help = "Stop a phase after this many seconds without improvement (requires -x)")]
+    pub stagnation_secs: Option<u64>,
+
+    /// Stop a phase when it has not improved in this many consecutive attempts
+    #[arg(long, requires = "early_termination", help = "Stop a phase after this many attempts without improvement (requires -x)")]
+    pub stagnation_attempts: Option<usize>,
+
     #[arg(short = 's', long, help = "Fixed seed for the random number generator")]
     pub rng_seed: Option<u64>,
+
+    /// Path to write the event stream to, one JSON object per report ('-' for stdout, the log then goes to stderr)
+    #[arg(long, help = "Write every report as a line of JSON to this file ('-' for stdout, logs go to stderr)")]
+    pub events: Option<String>,
+
+    /// Port on localhost to serve the live view on, disabled if not set
+    #[arg(long, help = "Serve a live view of all jobs on this port of localhost")]
+    pub live_port: Option<u16>,
+
+    /// Record every reported solution of a job, to be replayed with the `replay` binary
+    #[arg(long, help = "Record every reported solution to recording.json in the output directory of the job")]
+    pub record: bool,
+
+    /// Overlays a heatmap of the collisions on the intermediate infeasible solutions, see [`crate::util::heatmap::add_heatmap`]
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "weighted-loss", value_name = "METRIC", help = "Overlay a heatmap of the collisions on the intermediate infeasible solutions")]
+    pub heatmap: Option<HeatmapMetric>,
+
+    /// File from which commands are read while running: `dump`, `next`, `time <seconds>` or `stop`, one per line
+    #[arg(long, help = "Read commands (dump, next, time <secs>, stop) from this file while running")]
+    pub control: Option<String>,
+
+    /// Accept worse placements in the separators with a decaying probability, see [`crate::optimizer::separator::AcceptanceCriterion::Annealing`]
+    #[arg(long, help = "Use simulated annealing instead of greedy acceptance of moves in the separator")]
+    pub annealing: bool,
+
+    #[arg(long, requires = "annealing", default_value_t = ANNEALING_T_INIT, help = "Initial temperature of the annealing (requires --annealing)")]
+    pub t_init: f64,
+
+    #[arg(long, requires = "annealing", default_value_t = ANNEALING_T_DECAY, help = "Decay of the temperature per iteration (requires --annealing)")]
+    pub t_decay: f64,
+
+    /// Perturbs stalled separations by clearing a region and re-inserting its items, see [`crate::optimizer::ruin_recreate`]
+    #[arg(long, help = "Ruin and recreate a region around the highest-loss pairs when a separation stalls")]
+    pub ruin_recreate: bool,
+
+    /// Moves all items jointly with a gradient descent whenever a compression attempt fails, see [`crate::optimizer::polish`]
+    #[arg(long, help = "Polish the layout by moving all items jointly when a compression attempt fails")]
+    pub polish: bool,
+
+    /// Learns a distribution over the rotations of accepted placements of every item with continuous rotation, included in the output
+    #[arg(long, help = "Learn which rotations work for every item and include them in the output")]
+    pub learn_rotations: bool,
+
+    /// Number of rotations sampled for a single item, overriding the default for that item (can be repeated)
+    #[arg(long, value_name = "ITEM_ID=N", value_parser = parse_item_rotation_samples, help = "Sample N rotations for the item with this id (can be repeated)")]
+    pub rotation_samples: Vec<(usize, usize)>,
+
+    /// Adapts the number of samples and coordinate descents of every item to the outcome of its searches, see [`crate::sample::budget`]
+    #[arg(long, help = "Adapt the sample budgets of every item to where its improvements come from")]
+    pub adaptive_budget: bool,
+
+    /// Multiplier of the weight of the colliding pair with the largest loss, see [`crate::quantify::tracker::WeightSchedule`]
+    #[arg(long, default_value_t = GLS_WEIGHT_MAX_INC_RATIO, help = "Weight multiplier of the collision with the largest loss")]
+    pub gls_max_inc: f64,
+
+    /// Multiplier of the weight of a colliding pair with (almost) no loss
+    #[arg(long, default_value_t = GLS_WEIGHT_MIN_INC_RATIO, help = "Weight multiplier of a collision with (almost) no loss")]
+    pub gls_min_inc: f64,
+
+    /// Multiplier of the weight of a pair which is not colliding
+    #[arg(long, default_value_t = GLS_WEIGHT_DECAY, help = "Weight multiplier of a pair which is not colliding")]
+    pub gls_decay: f64,
+
+    /// Only increase the weights of this many colliding pairs with the largest loss, see [`crate::quantify::tracker::WeightSelection::TopK`]
+    #[arg(long, help = "Only increase the weights of the K colliding pairs with the largest loss")]
+    pub gls_top_k: Option<usize>,
+
+    /// Upper bound of all weights, uncapped if not set
+    #[arg(long, help = "Upper bound of all weights")]
+    pub gls_max_weight: Option<f64>,
+
+    /// Rounds the exported solution to this many decimals, resolving any collisions caused by the rounding
+    #[arg(long, help = "Round the exported solution to this many decimals, keeping it feasible")]
+    pub decimals: Option<usize>,
+}
+
+/// Parses an `ITEM_ID=N` argument of `--rotation-samples`
+fn parse_item_rotation_samples(arg: &str) -> Result<(usize, usize)> {
+    let (item_id, n_samples) = arg.split_once('=').context("expected ITEM_ID=N")?;
+    let n_samples = n_samples.trim().parse::<usize>()?;
+    ensure!(n_samples > 0, "at least one rotation has to be sampled");
+    Ok((item_id.trim().parse()?, n_samples))
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -43,9 +154,13 @@ pub struct SPOutput {
     #[serde(flatten)]
     pub instance: ExtSPInstance,
     pub solution: ExtSPSolution,
+    /// Distributions over the rotations learned during the run, see [`crate::sample::rotation::RotationModel::learned_histograms`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rotation_histograms: Vec<RotationHistogram>,
 }
 
-pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path) -> Result<()> {
+/// Logs to the file and to stdout, or to stderr if stdout is reserved for other output (e.g. the event stream)
+pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path, log_to_stderr: bool) -> Result<()> {
     //remove old log file
     let _ = fs::remove_file(log_file_path);
     fern::Dispatch::new()
@@ -72,7 +187,10 @@ pub fn init_logger(level_filter: LevelFilter, log_file_path: &Path) -> Result<()
         })
         // Add blanket level filter -
         .level(level_filter)
-        .chain(std::io::stdout())
+        .chain(match log_to_stderr {
+            true => fern::Output::from(std::io::stderr()),
+            false => fern::Output::from(std::io::stdout()),
+        })
         .chain(fern::log_file(log_file_path)?)
         .apply()?;
     log!(
@@ -113,43 +231,104 @@ pub fn write_json(json: &impl Serialize, path: &Path, log_lvl: Level) -> Result<
     Ok(())
 }
 
-pub fn write_csv(solution: &ExtSPSolution, path: &Path) -> Result<()> {
-    // 1. Kiểm tra xem file có cần ghi header không 
-    // (Cần ghi nếu file chưa tồn tại hoặc kích thước = 0)
+/// Appends the placed items of a solution to a CSV file, with all values written to `decimals` decimals if provided.
+/// The id of every row is `{number of items}_{item id}_{copy index}`, which identifies the placed item across solutions.
+pub fn write_csv(solution: &ExtSPSolution, path: &Path, decimals: Option<usize>) -> Result<()> {
+    //the header is only written to a new (or empty) file
     let write_header = !path.exists() || std::fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
 
-    // 2. Mở file với chế độ Append (Ghi tiếp)
+    //rows are appended to the existing ones
     let mut file = OpenOptions::new()
-        .create(true) // Tạo file nếu chưa có
-        .append(true) // Ghi tiếp vào cuối file nếu đã có
+        .create(true)
+        .append(true)
         .open(path)?;
 
-    // 3. Chỉ ghi header nếu là file mới
     if write_header {
         writeln!(file, "id,x,y,deg")?;
     }
 
-    // 4. Ghi dữ liệu
     let size = solution.layout.placed_items.len();
-    let size_str = format!("{:0>3}", size); // Format số lượng item (ví dụ 050)
+    let size_str = format!("{:0>3}", size); // number of items, zero-padded (e.g. 050)
 
-    // Lưu ý: Nếu placed_items là SlotMap (thường thấy trong jagua-rs), 
-    // .iter() trả về (key, value). Nếu item là value, bạn cần destruct hoặc dùng .values()
-    for (i, item) in solution.layout.placed_items.iter().enumerate() {
-        // Tùy vào cấu trúc ExtSPSolution của bạn, nếu item là tuple (key, val) thì dùng item.1
-        // Ở đây giữ nguyên logic truy cập field như code gốc của bạn
+    for item in solution.layout.placed_items.iter() {
+        let id = format!("{}_{}_{}", size_str, item.item_id, item.copy_idx);
         let x = item.transformation.translation.0;
         let y = item.transformation.translation.1;
         let rotation = item.transformation.rotation;
-        
-        writeln!(file, "{}_{},s{},s{},s{}", size_str, i, x, y, rotation)?;
+
+        match decimals {
+            Some(d) => writeln!(file, "{},s{:.d$},s{:.d$},s{:.d$}", id, x, y, rotation)?,
+            None => writeln!(file, "{},s{},s{},s{}", id, x, y, rotation)?,
+        }
     }
 
     Ok(())
 }
 
+/// Reads the solutions of a CSV file written by [`write_csv`], grouped by their number of items (in order of appearance).
+/// Ids of the form `{number of items}_{copy index}` (written by earlier versions and `convert.py`) are read as copies of item 0.
+pub fn read_csv(path: &Path) -> Result<Vec<(usize, Vec<ExtPlacedItem>)>> {
+    let content = fs::read_to_string(path).context("could not open CSV file")?;
+    let mut solutions: Vec<(usize, Vec<ExtPlacedItem>)> = vec![];
+    for (i, line) in content.lines().enumerate().skip(1).filter(|(_, l)| !l.trim().is_empty()) {
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        ensure!(fields.len() == 4, "line {}: expected 4 fields (id,x,y,deg), found {}", i + 1, fields.len());
+        let id = fields[0].split('_')
+            .map(str::parse::<usize>)
+            .collect::<Result<Vec<_>, _>>()
+            .ok();
+        let (n_items, item_id, copy_idx) = match id.as_deref() {
+            Some(&[n_items, item_id, copy_idx]) => (n_items, item_id, copy_idx),
+            Some(&[n_items, copy_idx]) => (n_items, 0, copy_idx),
+            _ => bail!("line {}: invalid id, expected {{number of items}}_{{item id}}_{{copy index}}: {}", i + 1, fields[0]),
+        };
+        //values are prefixed with 's' to preserve them as strings
+        let values = fields[1..].iter()
+            .map(|f| f.trim_start_matches('s').parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .context(format!("line {}: invalid value", i + 1))?;
+        let placed_item = ExtPlacedItem {
+            item_id: item_id as u64,
+            copy_idx: copy_idx as u64,
+            transformation: ExtTransformation {
+                rotation: values[2],
+                translation: (values[0], values[1]),
+            },
+        };
+        match solutions.last_mut() {
+            Some((n, placed_items)) if *n == n_items && placed_items.len() < n_items => placed_items.push(placed_item),
+            _ => solutions.push((n_items, vec![placed_item])),
+        }
+    }
+    Ok(solutions)
+}
+
 pub fn read_spp_instance_json(path: &Path) -> Result<ExtSPInstance> {
     let file = File::open(path).context("could not open instance file")?;
     serde_json::from_reader(BufReader::new(file))
         .context("not a valid strip packing instance (ExtSPInstance)")
 }
+
+pub fn read_spp_output_json(path: &Path) -> Result<SPOutput> {
+    let file = File::open(path).context("could not open solution file")?;
+    serde_json::from_reader(BufReader::new(file))
+        .context("not a valid strip packing solution (SPOutput)")
+}
+
+/// Rebuilds the layout of an exported solution, the instance should contain all placed items
+pub fn import_layout(instance: &SPInstance, solution: &ExtSPSolution) -> Result<Layout> {
+    let mut strip = instance.base_strip;
+    strip.set_width(solution.strip_width);
+    let mut layout = Layout::new(strip.into());
+    for ext_pi in &solution.layout.placed_items {
+        let item_id = ext_pi.item_id as usize;
+        ensure!(item_id < instance.items.len(), "placed item {item_id} is not part of the instance");
+        let item = instance.item(item_id);
+        //exported rotations are in degrees
+        let ext_transf = DTransformation::new(ext_pi.transformation.rotation.to_radians(), ext_pi.transformation.translation);
+        let copy_idx = ext_pi.copy_idx as usize;
+        ensure!(!layout.is_copy_placed(item_id, copy_idx), "copy {copy_idx} of item {item_id} is placed more than once");
+        layout.place_item_copy(item, ext_to_int_transformation(&ext_transf, &item.shape_orig.pre_transform), copy_idx);
+    }
+    Ok(layout)
+}
@@ -1,8 +1,39 @@
+use crate::optimizer::worker::SepStats;
+use crate::quantify::tracker::CollisionTracker;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use std::time::Duration;
 
 /// Trait for listeners that can receive solutions during the optimization process
 pub trait SolutionListener {
     fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance);
+
+    /// Receives the progress of the separator, before every report it makes and at the end of every separation
+    fn report_progress(&mut self, _progress: &SepProgress) {}
+
+    /// Receives the collision tracker of the infeasible solution which is reported next ([`ReportType::ExplInfeas`])
+    fn report_tracker(&mut self, _ct: &CollisionTracker) {}
+}
+
+/// Progress of a single call to [`Separator::separate`](crate::optimizer::separator::Separator::separate)
+#[derive(Debug, Clone, Copy)]
+pub struct SepProgress {
+    /// Lowest total loss reached so far
+    pub total_loss: f64,
+    pub stats: SepStats,
+    /// Time spent separating so far
+    pub elapsed: Duration,
+    /// Whether the separation has finished
+    pub finished: bool,
+}
+
+impl SepProgress {
+    pub fn evals_per_sec(&self) -> f64 {
+        self.stats.total_evals as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn moves_per_sec(&self) -> f64 {
+        self.stats.total_moves as f64 / self.elapsed.as_secs_f64()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +50,17 @@ pub enum ReportType {
     Final
 }
 
+impl ReportType {
+    /// Name of the optimization phase the report originates from
+    pub fn phase(&self) -> &'static str {
+        match self {
+            ReportType::ExplFeas | ReportType::ExplInfeas | ReportType::ExplImproving => "exploration",
+            ReportType::CmprFeas => "compression",
+            ReportType::Final => "final",
+        }
+    }
+}
+
 /// A dummy implementation of the `SolutionListener` trait that does nothing.
 pub struct DummySolListener;
 
@@ -27,3 +69,57 @@ impl SolutionListener for DummySolListener {
         // Do nothing
     }
 }
+
+/// Forwards every report to both listeners
+impl<A: SolutionListener, B: SolutionListener> SolutionListener for (A, B) {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        self.0.report(report.clone(), solution, instance);
+        self.1.report(report, solution, instance);
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        self.0.report_progress(progress);
+        self.1.report_progress(progress);
+    }
+
+    fn report_tracker(&mut self, ct: &CollisionTracker) {
+        self.0.report_tracker(ct);
+        self.1.report_tracker(ct);
+    }
+}
+
+/// Forwards every report to the listener, if present
+impl<L: SolutionListener> SolutionListener for Option<L> {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        if let Some(listener) = self {
+            listener.report(report, solution, instance);
+        }
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        if let Some(listener) = self {
+            listener.report_progress(progress);
+        }
+    }
+
+    fn report_tracker(&mut self, ct: &CollisionTracker) {
+        if let Some(listener) = self {
+            listener.report_tracker(ct);
+        }
+    }
+}
+
+/// Forwards every report to a borrowed listener, which outlives the run (e.g. one shared by all jobs of a batch)
+impl<L: SolutionListener> SolutionListener for &mut L {
+    fn report(&mut self, report: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        (**self).report(report, solution, instance);
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        (**self).report_progress(progress);
+    }
+
+    fn report_tracker(&mut self, ct: &CollisionTracker) {
+        (**self).report_tracker(ct);
+    }
+}
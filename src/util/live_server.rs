@@ -0,0 +1,172 @@
+use crate::consts::DRAW_OPTIONS;
+use crate::util::event_stream::ReportEvent;
+use crate::util::listener::{ReportType, SepProgress, SolutionListener};
+use anyhow::{Context, Result};
+use jagua_rs::io::svg::s_layout_to_svg;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Viewer served at the root of the live server
+const VIEWER_HTML: &str = include_str!("../../data/live/live_server_viewer.html");
+
+/// Clients which do not send their request or accept an update within this time are disconnected, so they cannot stall the optimization
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Minimum time between two intermediate updates of a job, feasible and final solutions are always pushed
+const MIN_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Minimal HTTP server, bound to localhost, which serves a viewer and pushes the layouts of one or more jobs
+/// to all connected viewers over server-sent events.
+/// Every job reports through its own [`LiveViewListener`], see [`LiveServer::listener`].
+pub struct LiveServer {
+    pub addr: SocketAddr,
+    /// Connected event streams
+    clients: Mutex<Vec<TcpStream>>,
+    /// Latest message of every job, sent to viewers as soon as they connect
+    latest: Mutex<BTreeMap<String, String>>,
+}
+
+/// A single update pushed to the viewers
+#[derive(Serialize)]
+struct LiveUpdate<'a> {
+    job: &'a str,
+    #[serde(flatten)]
+    event: ReportEvent,
+    svg: String,
+}
+
+impl LiveServer {
+    /// Binds the server to the given port on localhost and starts accepting connections on a background thread
+    pub fn start(port: u16) -> Result<Arc<Self>> {
+        let tcp_listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .context(format!("could not bind live server to port {port}"))?;
+        let server = Arc::new(Self {
+            addr: tcp_listener.local_addr()?,
+            clients: Mutex::new(vec![]),
+            latest: Mutex::new(BTreeMap::new()),
+        });
+        info!("[LIVE] live view available at http://{}", server.addr);
+
+        let s = server.clone();
+        std::thread::Builder::new()
+            .name("live-server".into())
+            .spawn(move || {
+                for stream in tcp_listener.incoming().flatten() {
+                    if let Err(e) = s.handle_connection(stream) {
+                        debug!("[LIVE] failed to handle connection: {e}");
+                    }
+                }
+            })?;
+        Ok(server)
+    }
+
+    /// Creates a listener which pushes the reports of a job to the viewers
+    pub fn listener(self: &Arc<Self>, job: impl Into<String>) -> LiveViewListener {
+        LiveViewListener {
+            server: self.clone(),
+            job: job.into(),
+            progress: None,
+            last_update: None,
+        }
+    }
+
+    pub fn has_clients(&self) -> bool {
+        !self.clients.lock().unwrap().is_empty()
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(stream.try_clone()?);
+        reader.read_line(&mut request_line)?;
+        //skip the headers, the request itself is all that matters
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        match request_line.split_whitespace().nth(1) {
+            Some("/") => {
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    VIEWER_HTML.len(), VIEWER_HTML)?;
+            }
+            Some("/events") => {
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")?;
+                stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+                for message in self.latest.lock().unwrap().values() {
+                    stream.write_all(message.as_bytes())?;
+                }
+                self.clients.lock().unwrap().push(stream);
+            }
+            _ => write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?,
+        }
+        Ok(())
+    }
+
+    /// Sends an update of a job to all connected viewers, disconnecting those which fail to receive it
+    fn publish(&self, job: &str, update: &LiveUpdate) {
+        let message = match serde_json::to_string(update) {
+            Ok(json) => format!("event: layout\ndata: {json}\n\n"),
+            Err(e) => {
+                warn!("[LIVE] failed to serialize update: {e}");
+                return;
+            }
+        };
+        self.clients.lock().unwrap()
+            .retain_mut(|client| client.write_all(message.as_bytes()).is_ok());
+        self.latest.lock().unwrap().insert(job.to_string(), message);
+    }
+}
+
+/// Listener which pushes the reports of a job to the viewers of a [`LiveServer`].
+/// Nothing is rendered while no viewer is connected, except for the final solution,
+/// and intermediate solutions are pushed at most once every [`MIN_UPDATE_INTERVAL`].
+pub struct LiveViewListener {
+    server: Arc<LiveServer>,
+    job: String,
+    /// Latest progress of the separator
+    progress: Option<SepProgress>,
+    /// Time of the latest update pushed to the viewers
+    last_update: Option<Instant>,
+}
+
+impl LiveViewListener {
+    fn should_publish(&self, report_type: &ReportType) -> bool {
+        match report_type {
+            //kept for viewers which connect after the job has finished
+            ReportType::Final => true,
+            _ if !self.server.has_clients() => false,
+            ReportType::ExplInfeas | ReportType::ExplImproving => {
+                self.last_update.is_none_or(|t| t.elapsed() >= MIN_UPDATE_INTERVAL)
+            }
+            ReportType::ExplFeas | ReportType::CmprFeas => true,
+        }
+    }
+}
+
+impl SolutionListener for LiveViewListener {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        if !self.should_publish(&report_type) {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+        let title = format!("{}_{:.3}_{}", self.job, solution.strip_width(), report_type.phase());
+        let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, &title);
+        let update = LiveUpdate {
+            job: &self.job,
+            event: ReportEvent::new(report_type, solution, instance, self.progress.as_ref()),
+            svg: svg.to_string(),
+        };
+        self.server.publish(&self.job, &update);
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        self.progress = Some(*progress);
+    }
+}
@@ -2,9 +2,21 @@ pub mod assertions;
 
 pub mod io;
 pub mod bit_reversal_iterator;
+pub mod early_terminator;
+pub mod event_stream;
+pub mod heatmap;
 pub mod listener;
+pub mod replay;
+pub mod rounding;
 pub mod svg_exporter;
 pub mod terminator;
+pub mod validator;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod ctrlc_terminator;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod live_server;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod run_controller;
@@ -0,0 +1,201 @@
+use crate::consts::DRAW_OPTIONS;
+use crate::util::io;
+use crate::util::listener::{ReportType, SepProgress, SolutionListener};
+use crate::EPOCH;
+use anyhow::{ensure, Context, Result};
+use jagua_rs::entities::{Instance, Layout};
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::io::svg::s_layout_to_svg;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::{warn, Level};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Template of the HTML player, see [`html_player`]
+const PLAYER_HTML: &str = include_str!("../../data/live/replay_player.html");
+
+/// Maximum time between two writes of an unfinished recording, so an aborted run leaves most of its frames behind
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Every reported solution of a run, in compact form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub instance_name: String,
+    pub frames: Vec<Frame>,
+}
+
+/// A single reported solution: the container and the placement of every item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frame {
+    pub report_type: String,
+    /// Seconds since the start of the process
+    pub elapsed_s: f64,
+    pub width: f64,
+    pub height: f64,
+    pub density: f64,
+    /// Total loss of the latest separation, zero for feasible solutions
+    pub total_loss: f64,
+    /// (item id, rotation, x, y) of every placed item
+    pub placements: Vec<(usize, f64, f64, f64)>,
+}
+
+/// Listener which records every report in a [`Recording`], written to `path` when the final solution is reported.
+/// Unfinished recordings are written every [`SAVE_INTERVAL`] and when the recorder is dropped (e.g. after a panic).
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+    pub recording: Recording,
+    /// Latest progress of the separator
+    progress: Option<SepProgress>,
+    /// Number of frames in the file at `path`
+    n_saved_frames: usize,
+    last_save: Instant,
+}
+
+impl ReplayRecorder {
+    pub fn new(path: impl Into<PathBuf>, instance_name: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            recording: Recording { instance_name: instance_name.into(), frames: vec![] },
+            progress: None,
+            n_saved_frames: 0,
+            last_save: Instant::now(),
+        }
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        self.write(Level::Info)
+    }
+
+    fn write(&mut self, log_lvl: Level) -> Result<()> {
+        self.last_save = Instant::now();
+        io::write_json(&self.recording, &self.path, log_lvl)?;
+        self.n_saved_frames = self.recording.frames.len();
+        Ok(())
+    }
+
+    fn save_or_warn(&mut self, log_lvl: Level) {
+        if let Err(e) = self.write(log_lvl) {
+            warn!("failed to write recording: {e}");
+        }
+    }
+}
+
+impl Drop for ReplayRecorder {
+    fn drop(&mut self) {
+        if self.recording.frames.len() > self.n_saved_frames {
+            self.save_or_warn(Level::Info);
+        }
+    }
+}
+
+impl SolutionListener for ReplayRecorder {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        let total_loss = match report_type {
+            ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final => 0.0,
+            ReportType::ExplInfeas | ReportType::ExplImproving => self.progress.map_or(0.0, |p| p.total_loss),
+        };
+        let placements = solution.layout_snapshot.placed_items.values()
+            .map(|pi| {
+                let (x, y) = pi.d_transf.translation();
+                (pi.item_id, pi.d_transf.rotation(), x, y)
+            })
+            .collect();
+        self.recording.frames.push(Frame {
+            report_type: format!("{report_type:?}"),
+            elapsed_s: EPOCH.elapsed().as_secs_f64(),
+            width: solution.strip.width,
+            height: solution.strip.fixed_height,
+            density: solution.density(instance),
+            total_loss,
+            placements,
+        });
+        match report_type {
+            ReportType::Final => self.save_or_warn(Level::Info),
+            _ if self.last_save.elapsed() >= SAVE_INTERVAL => self.save_or_warn(Level::Debug),
+            _ => {}
+        }
+    }
+
+    fn report_progress(&mut self, progress: &SepProgress) {
+        self.progress = Some(*progress);
+    }
+}
+
+pub fn read_recording(path: &Path) -> Result<Recording> {
+    let file = File::open(path).context("could not open recording file")?;
+    serde_json::from_reader(BufReader::new(file)).context("not a valid recording")
+}
+
+/// Renders every frame of a recording with [`s_layout_to_svg`], the instance should contain the recorded items
+pub fn render_frames(recording: &Recording, instance: &SPInstance) -> Result<Vec<String>> {
+    recording.frames.iter().enumerate()
+        .map(|(i, frame)| {
+            let mut strip = instance.base_strip;
+            strip.fixed_height = frame.height;
+            strip.set_width(frame.width);
+            let mut layout = Layout::new(strip.into());
+            //copies are not recorded, they are irrelevant for rendering
+            for &(item_id, r, x, y) in &frame.placements {
+                ensure!(item_id < instance.items.len(), "recorded item {item_id} is not part of the instance");
+                layout.place_item(instance.item(item_id), DTransformation::new(r, (x, y)));
+            }
+            let title = format!("{}/{} {}", i + 1, recording.frames.len(), frame.report_type);
+            Ok(s_layout_to_svg(&layout.save(), instance, DRAW_OPTIONS, &title).to_string())
+        })
+        .collect()
+}
+
+/// Single SVG which shows the frames one after the other, every frame visible for `frame_duration_s` seconds
+pub fn animated_svg(frames: &[String], frame_duration_s: f64) -> String {
+    let n = frames.len();
+    let total_duration = frame_duration_s * n as f64;
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100%" height="100%">"#);
+    for (i, frame) in frames.iter().enumerate() {
+        //every frame is only visible during its own window of the loop
+        let (start, end) = (i as f64 / n as f64, (i + 1) as f64 / n as f64);
+        write!(svg, r#"<g visibility="hidden"><animate attributeName="visibility" dur="{total_duration}s" repeatCount="indefinite" calcMode="discrete" keyTimes="0;{start};{end}" values="hidden;visible;hidden"/>{}</g>"#,
+            strip_xml_declaration(frame)).unwrap();
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Self-contained HTML player of the frames, with a timeline of the container size and loss
+pub fn html_player(recording: &Recording, frames: &[String]) -> Result<String> {
+    let frames_json = serde_json::to_string(&frames.iter().map(|f| strip_xml_declaration(f)).collect::<Vec<_>>())?;
+    let stats_json = serde_json::to_string(&recording.frames.iter()
+        .map(|f| (f.elapsed_s, f.width, f.height, f.total_loss, f.density, &f.report_type))
+        .collect::<Vec<_>>())?;
+    //the json is embedded in a script element, which must not be closed by any of its strings
+    Ok(PLAYER_HTML
+        .replace("{{TITLE}}", &escape_html(&recording.instance_name))
+        .replace("{{FRAMES}}", &frames_json.replace("</", "<\\/"))
+        .replace("{{STATS}}", &stats_json.replace("</", "<\\/")))
+}
+
+fn strip_xml_declaration(svg: &str) -> &str {
+    match svg.starts_with("<?xml") {
+        true => svg.find("?>").map_or(svg, |i| svg[i + 2..].trim_start()),
+        false => svg,
+    }
+}
+
+/// Escapes the characters with a special meaning in HTML text
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use anyhow::{bail, Context, Result};
+use jagua_rs::geometry::primitives::Point;
+use jagua_rs::io::ext_repr::ExtTransformation;
+use crate::consts::MAX_ROUNDING_ITERATIONS;
+use crate::optimizer::separator::ContainerMode;
+use crate::util::io::SPOutput;
+use crate::util::validator::{original_shape, placed_shape, validate};
+
+/// Outcome of [`round_output`]
+#[derive(Debug, Clone, Copy)]
+pub struct RoundingReport {
+    pub decimals: usize,
+    /// Number of collisions and containment violations right after rounding
+    pub n_violations: usize,
+    /// Number of correction steps to resolve them
+    pub n_iterations: usize,
+    /// Size of the container (width of the strip or side of the square) before and after rounding
+    pub size_before: f64,
+    pub size_after: f64,
+}
+
+impl RoundingReport {
+    pub fn size_lost(&self) -> f64 {
+        self.size_after - self.size_before
+    }
+}
+
+impl Display for RoundingReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rounded to {} decimals, {} violations resolved in {} iterations, size lost: {:.3e} ({} -> {})",
+            self.decimals, self.n_violations, self.n_iterations, self.size_lost(), self.size_before, self.size_after)
+    }
+}
+
+/// Rounds all transformations of a solution to a number of decimals, such that it remains feasible when written with that precision.
+/// Items which collide after rounding are pushed apart by the smallest representable step (10^-decimals) until no collisions remain.
+/// Items pushed across a wall which can grow (the right side of a strip, the right and top sides of a square) regrow the container, other items are pushed back inside.
+///
+/// Collisions are checked on the original shapes with [`validate`] and the given `tolerance`.
+pub fn round_output(output: &SPOutput, container: ContainerMode, decimals: usize, tolerance: f64) -> Result<(SPOutput, RoundingReport)> {
+    let step = 10f64.powi(-(decimals as i32));
+    let mut output = output.clone();
+    let size_before = output.solution.strip_width;
+    let (mut width, mut height) = (output.solution.strip_width, output.instance.strip_height);
+
+    let shapes = output.instance.items.iter()
+        .map(|item| Ok((item.base.id, original_shape(&item.base.shape)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    let placed_items = &mut output.solution.layout.placed_items;
+    for pi in placed_items.iter_mut() {
+        pi.transformation = round_transformation(&pi.transformation, decimals);
+    }
+
+    let mut n_violations = 0;
+    let mut n_iterations = 0;
+    loop {
+        let report = validate(&output.instance, placed_items, Some((width, height)), tolerance)?;
+        let n = report.collisions.len() + report.containment_violations.len();
+        if n_iterations == 0 {
+            n_violations = n;
+        }
+        if n == 0 {
+            break;
+        }
+        if n_iterations == MAX_ROUNDING_ITERATIONS {
+            bail!("{n} violations remain after rounding to {decimals} decimals and {n_iterations} correction steps");
+        }
+        n_iterations += 1;
+
+        let placed_shapes = placed_items.iter()
+            .map(|pi| {
+                let original = shapes.get(&pi.item_id).context(format!("item {} is not part of the instance", pi.item_id))?;
+                Ok(placed_shape(original, &pi.transformation))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        //push every colliding pair apart, along the line through their centroids
+        let mut deltas = vec![(0.0, 0.0); placed_items.len()];
+        for c in &report.collisions {
+            let (Point(x1, y1), Point(x2, y2)) = (placed_shapes[c.idxs.0].centroid(), placed_shapes[c.idxs.1].centroid());
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let norm = f64::hypot(dx, dy);
+            let (dx, dy) = match norm > 0.0 {
+                true => ((dx / norm).round() * step, (dy / norm).round() * step),
+                false => (step, 0.0),
+            };
+            deltas[c.idxs.0].0 -= dx;
+            deltas[c.idxs.0].1 -= dy;
+            deltas[c.idxs.1].0 += dx;
+            deltas[c.idxs.1].1 += dy;
+        }
+
+        //push items back across the fixed walls and regrow the container up to the items which stick out of the others
+        for v in &report.containment_violations {
+            let bbox = placed_shapes[v.idx].bbox;
+            if bbox.x_min < 0.0 {
+                deltas[v.idx].0 += ceil_to(-bbox.x_min, decimals);
+            }
+            if bbox.y_min < 0.0 {
+                deltas[v.idx].1 += ceil_to(-bbox.y_min, decimals);
+            }
+            width = f64::max(width, ceil_to(bbox.x_max, decimals));
+            match container {
+                ContainerMode::Strip if bbox.y_max > height => deltas[v.idx].1 -= ceil_to(bbox.y_max - height, decimals),
+                ContainerMode::Strip => {}
+                ContainerMode::Square => height = f64::max(height, ceil_to(bbox.y_max, decimals)),
+            }
+        }
+        if container == ContainerMode::Square {
+            width = f64::max(width, height);
+            height = width;
+        }
+
+        for (pi, (dx, dy)) in placed_items.iter_mut().zip(deltas) {
+            let (x, y) = pi.transformation.translation;
+            pi.transformation.translation = (round_to(x + dx, decimals), round_to(y + dy, decimals));
+        }
+    }
+
+    let size_after = width;
+    output.solution.density *= (size_before * output.instance.strip_height) / (width * height);
+    output.solution.strip_width = width;
+    output.instance.strip_height = height;
+
+    let report = RoundingReport { decimals, n_violations, n_iterations, size_before, size_after };
+    Ok((output, report))
+}
+
+fn round_transformation(t: &ExtTransformation, decimals: usize) -> ExtTransformation {
+    ExtTransformation {
+        rotation: round_to(t.rotation, decimals),
+        translation: (round_to(t.translation.0, decimals), round_to(t.translation.1, decimals)),
+    }
+}
+
+pub fn round_to(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    //adding zero turns -0.0 into 0.0
+    (value * factor).round() / factor + 0.0
+}
+
+fn ceil_to(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).ceil() / factor
+}
@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use jagua_rs::Instant;
+use jagua_rs::io::svg::s_layout_to_svg;
+use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+use log::{info, warn, Level};
+use crate::consts::DRAW_OPTIONS;
+use crate::EPOCH;
+use crate::util::io;
+use crate::util::listener::{ReportType, SolutionListener};
+use crate::util::terminator::{KillReason, Terminator};
+
+/// Interval at which the control file and signals are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Command to steer a running optimization process, see [`RunController`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlCommand {
+    /// Writes the current best solution to the output directory of the job
+    Dump,
+    /// Ends the current phase, moving to the next one
+    NextPhase,
+    /// Sets the remaining time of the current phase
+    SetRemainingTime(Duration),
+    /// Ends the current job and skips all remaining ones
+    Stop,
+}
+
+impl ControlCommand {
+    /// Parses a line of the control file: `dump`, `next`, `time <seconds>` or `stop`
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut words = line.split_whitespace();
+        let command = match (words.next(), words.next()) {
+            (Some("dump"), None) => ControlCommand::Dump,
+            (Some("next"), None) => ControlCommand::NextPhase,
+            (Some("time"), Some(secs)) => {
+                let secs = secs.parse::<f64>().context(format!("invalid number of seconds: {secs}"))?;
+                ControlCommand::SetRemainingTime(Duration::try_from_secs_f64(secs)?)
+            }
+            (Some("stop"), None) => ControlCommand::Stop,
+            _ => bail!("unknown command: {line}"),
+        };
+        match words.next() {
+            Some(_) => bail!("unknown command: {line}"),
+            None => Ok(command),
+        }
+    }
+}
+
+/// Latest feasible solution of the running job, only rendered and exported when it is dumped
+struct BestSolution {
+    dir: PathBuf,
+    instance: Arc<SPInstance>,
+    solution: SPSolution,
+}
+
+#[derive(Default)]
+struct ControlState {
+    /// Deadline of the current phase which overrides the timeout of the inner terminator, if set
+    deadline: Mutex<Option<Instant>>,
+    next_phase: AtomicBool,
+    stop: AtomicBool,
+    best: Mutex<Option<BestSolution>>,
+    n_dumps: AtomicUsize,
+}
+
+impl ControlState {
+    fn execute(&self, command: ControlCommand) {
+        info!("[CTRL] received command: {command:?}");
+        match command {
+            ControlCommand::Dump => {
+                if let Err(e) = self.dump() {
+                    warn!("[CTRL] failed to dump best solution: {e}");
+                }
+            }
+            ControlCommand::NextPhase => self.next_phase.store(true, Ordering::SeqCst),
+            ControlCommand::SetRemainingTime(remaining) => *self.deadline.lock().unwrap() = Some(Instant::now() + remaining),
+            ControlCommand::Stop => self.stop.store(true, Ordering::SeqCst),
+        }
+    }
+
+    fn dump(&self) -> Result<()> {
+        let best = self.best.lock().unwrap();
+        let Some(best) = best.as_ref() else {
+            bail!("no feasible solution reported yet");
+        };
+        let n = self.n_dumps.fetch_add(1, Ordering::SeqCst);
+        let svg = s_layout_to_svg(&best.solution.layout_snapshot, best.instance.as_ref(), DRAW_OPTIONS, &format!("dump_{:.3}", best.solution.strip_width()));
+        let ext_solution = jagua_rs::probs::spp::io::export(&best.instance, &best.solution, *EPOCH);
+        io::write_svg(&svg, &best.dir.join(format!("dump_{n}.svg")), Level::Info)?;
+        io::write_json(&ext_solution, &best.dir.join(format!("dump_{n}.json")), Level::Info)
+    }
+
+    /// Executes the commands of the control file and empties it
+    fn read_control_file(&self, path: &Path) -> Result<()> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            //no commands until the file is created
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+        fs::write(path, "")?;
+        for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            match ControlCommand::parse(line) {
+                Ok(command) => self.execute(command),
+                Err(e) => warn!("[CTRL] ignoring line of control file: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wraps another terminator (e.g. [`CtrlCTerminator`](crate::util::ctrlc_terminator::CtrlCTerminator)) and allows a running process to be steered
+/// with [`ControlCommand`]s, without killing it:
+/// - on Unix, `SIGUSR1` dumps the current best solution and `SIGUSR2` moves to the next phase.
+/// - every line written to the control file, if any, is executed as a command (see [`ControlCommand::parse`]), after which the file is emptied.
+///
+/// The current best solution is followed through the listener created by [`RunController::listener`].
+#[derive(Clone)]
+pub struct RunController<T: Terminator> {
+    pub inner: T,
+    state: Arc<ControlState>,
+}
+
+impl<T: Terminator> RunController<T> {
+    /// Sets up the signal handlers and starts watching the control file, if provided (only call once)
+    pub fn new(inner: T, control_file: Option<PathBuf>) -> Result<Self> {
+        let state = Arc::new(ControlState::default());
+        #[cfg(unix)]
+        signals::install();
+
+        let s = state.clone();
+        std::thread::Builder::new()
+            .name("run-controller".into())
+            .spawn(move || loop {
+                #[cfg(unix)]
+                for command in signals::received() {
+                    s.execute(command);
+                }
+                if let Some(path) = &control_file && let Err(e) = s.read_control_file(path) {
+                    warn!("[CTRL] failed to read control file: {e}");
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            })?;
+        Ok(Self { inner, state })
+    }
+
+    /// Creates the listener which keeps track of the best solution of a job, dumped to `dir` on request
+    pub fn listener(&self, dir: impl Into<PathBuf>) -> RunControlListener {
+        *self.state.best.lock().unwrap() = None;
+        RunControlListener {
+            dir: dir.into(),
+            state: self.state.clone(),
+            instance: None,
+        }
+    }
+
+    /// Whether a stop of all remaining jobs was requested
+    pub fn stop_requested(&self) -> bool {
+        self.state.stop.load(Ordering::SeqCst)
+    }
+}
+
+impl<T: Terminator> Terminator for RunController<T> {
+    fn kill(&self) -> bool {
+        self.kill_reason().is_some()
+    }
+
+    fn new_timeout(&mut self, timeout: Duration) {
+        self.inner.new_timeout(timeout);
+        *self.state.deadline.lock().unwrap() = None;
+        self.state.next_phase.store(false, Ordering::SeqCst);
+    }
+
+    fn timeout_at(&self) -> Option<Instant> {
+        self.state.deadline.lock().unwrap().or(self.inner.timeout_at())
+    }
+
+    fn kill_reason(&self) -> Option<KillReason> {
+        let deadline = *self.state.deadline.lock().unwrap();
+        if self.state.stop.load(Ordering::SeqCst) {
+            Some(KillReason::StopRequested)
+        } else if self.state.next_phase.load(Ordering::SeqCst) {
+            Some(KillReason::PhaseSkipped)
+        } else if let Some(deadline) = deadline {
+            //the timeout of the inner terminator is overridden
+            match self.inner.kill_reason() {
+                Some(KillReason::Timeout) | None => (Instant::now() > deadline).then_some(KillReason::Timeout),
+                reason => reason,
+            }
+        } else {
+            self.inner.kill_reason()
+        }
+    }
+}
+
+/// Keeps track of the best solution of a job for a [`RunController`]
+pub struct RunControlListener {
+    dir: PathBuf,
+    state: Arc<ControlState>,
+    /// Instance of the job, shared by all of its reported solutions
+    instance: Option<Arc<SPInstance>>,
+}
+
+impl SolutionListener for RunControlListener {
+    fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
+        if matches!(report_type, ReportType::ExplFeas | ReportType::CmprFeas | ReportType::Final) {
+            let instance = self.instance.get_or_insert_with(|| Arc::new(instance.clone()));
+            let best = BestSolution {
+                dir: self.dir.clone(),
+                instance: instance.clone(),
+                solution: solution.clone(),
+            };
+            *self.state.best.lock().unwrap() = Some(best);
+        }
+    }
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use super::ControlCommand;
+
+    static SIGUSR1_RECEIVED: AtomicBool = AtomicBool::new(false);
+    static SIGUSR2_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn on_sigusr1(_: libc::c_int) {
+        SIGUSR1_RECEIVED.store(true, Ordering::SeqCst);
+    }
+
+    extern "C" fn on_sigusr2(_: libc::c_int) {
+        SIGUSR2_RECEIVED.store(true, Ordering::SeqCst);
+    }
+
+    pub fn install() {
+        //the handlers only set a flag, which is async-signal-safe
+        unsafe {
+            libc::signal(libc::SIGUSR1, on_sigusr1 as extern "C" fn(libc::c_int) as libc::sighandler_t);
+            libc::signal(libc::SIGUSR2, on_sigusr2 as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+
+    /// Commands of the signals received since the last call
+    pub fn received() -> impl Iterator<Item = ControlCommand> {
+        let dump = SIGUSR1_RECEIVED.swap(false, Ordering::SeqCst).then_some(ControlCommand::Dump);
+        let next = SIGUSR2_RECEIVED.swap(false, Ordering::SeqCst).then_some(ControlCommand::NextPhase);
+        dump.into_iter().chain(next)
+    }
+}
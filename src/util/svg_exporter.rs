@@ -3,6 +3,8 @@ use jagua_rs::io::svg::s_layout_to_svg;
 use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
 use log::Level;
 use crate::consts::DRAW_OPTIONS;
+use crate::quantify::tracker::CollisionTracker;
+use crate::util::heatmap::{add_heatmap, HeatmapOptions};
 use crate::util::io;
 use crate::util::listener::{SolutionListener, ReportType};
 pub struct SvgExporter {
@@ -13,10 +15,14 @@ pub struct SvgExporter {
     pub intermediate_dir: Option<String>,
     /// Path to write the live SVG file to, if provided
     pub live_path: Option<String>,
+    /// Overlays a heatmap of the collision tracker on infeasible solutions ([`ReportType::ExplInfeas`]), if provided
+    pub heatmap: Option<HeatmapOptions>,
+    /// Collision tracker of the next infeasible solution
+    tracker: Option<CollisionTracker>,
 }
 
 impl SvgExporter {
-    pub fn new(final_path: Option<String>, intermediate_dir: Option<String>, live_path: Option<String>) -> Self {
+    pub fn new(final_path: Option<String>, intermediate_dir: Option<String>, live_path: Option<String>, heatmap: Option<HeatmapOptions>) -> Self {
         // Clean all svg files from the intermediate directory if it is provided
         if let Some(intermediate_dir) = &intermediate_dir {
             if let Ok(files_in_dir) = std::fs::read_dir(&Path::new(intermediate_dir)) {
@@ -33,11 +39,19 @@ impl SvgExporter {
             final_path,
             intermediate_dir,
             live_path,
+            heatmap,
+            tracker: None,
         }
     }
 }
 
 impl SolutionListener for SvgExporter{
+    fn report_tracker(&mut self, ct: &CollisionTracker) {
+        if self.heatmap.is_some() {
+            self.tracker = Some(ct.clone());
+        }
+    }
+
     fn report(&mut self, report_type: ReportType, solution: &SPSolution, instance: &SPInstance) {
         let suffix = match report_type {
             ReportType::CmprFeas => "cmpr",
@@ -47,12 +61,21 @@ impl SolutionListener for SvgExporter{
             ReportType::ExplImproving => "expl_i"
         };
         let file_name = format!("{}_{:.3}_{}", self.svg_counter, solution.strip_width(), suffix);
+        //the tracker only accompanies the report directly following it
+        let tracker = self.tracker.take().filter(|_| report_type == ReportType::ExplInfeas);
+        let render = |title: &str| {
+            let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, title);
+            match (self.heatmap, &tracker) {
+                (Some(options), Some(ct)) => add_heatmap(svg, &solution.layout_snapshot, ct, options),
+                _ => svg,
+            }
+        };
         if let Some(live_path) = &self.live_path {
-            let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, &file_name.as_str());
+            let svg = render(&file_name);
             io::write_svg(&svg, Path::new(live_path), Level::Trace).expect("failed to write live svg");
         }
         if let Some(intermediate_dir) = &self.intermediate_dir && report_type != ReportType::ExplImproving {
-            let svg = s_layout_to_svg(&solution.layout_snapshot, instance, DRAW_OPTIONS, file_name.as_str());
+            let svg = render(&file_name);
             let file_path = &*format!("{intermediate_dir}/{file_name}.svg");
             io::write_svg(&svg, Path::new(file_path), Level::Trace).expect("failed to write intermediate svg");
             self.svg_counter += 1;
@@ -1,5 +1,7 @@
+use std::fmt::{Display, Formatter};
 use std::time::Duration;
 use jagua_rs::Instant;
+use crate::util::early_terminator::Target;
 
 /// Generic trait for any struct that can determine if the optimization process should terminate.
 pub trait Terminator {
@@ -11,6 +13,46 @@ pub trait Terminator {
 
     /// Returns the instant when a timeout was set, if any
     fn timeout_at(&self) -> Option<Instant>;
+
+    /// Returns the condition which is causing the termination, if any
+    fn kill_reason(&self) -> Option<KillReason> {
+        self.timeout_at()
+            .filter(|timeout| Instant::now() > *timeout)
+            .map(|_| KillReason::Timeout)
+    }
+}
+
+/// Condition which caused a [`Terminator`] to terminate the optimization process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KillReason {
+    /// The timeout of the phase was reached
+    Timeout,
+    /// The process was interrupted by the user (Ctrl-C)
+    Interrupted,
+    /// A move to the next phase was requested, see [`RunController`](crate::util::run_controller::RunController)
+    PhaseSkipped,
+    /// A stop of all remaining jobs was requested, see [`RunController`](crate::util::run_controller::RunController)
+    StopRequested,
+    /// A feasible solution reached the target
+    TargetReached(Target),
+    /// No improvement was found within the given duration
+    StagnationTime(Duration),
+    /// No improvement was found within the given number of consecutive attempts
+    StagnationAttempts(usize),
+}
+
+impl Display for KillReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillReason::Timeout => write!(f, "timeout reached"),
+            KillReason::Interrupted => write!(f, "interrupted"),
+            KillReason::PhaseSkipped => write!(f, "next phase requested"),
+            KillReason::StopRequested => write!(f, "stop requested"),
+            KillReason::TargetReached(target) => write!(f, "target reached ({target})"),
+            KillReason::StagnationTime(d) => write!(f, "no improvement for {:.1}s", d.as_secs_f64()),
+            KillReason::StagnationAttempts(n) => write!(f, "no improvement in {n} consecutive attempts"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
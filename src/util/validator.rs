@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use anyhow::{bail, Context, Result};
+use jagua_rs::geometry::geo_traits::{CollidesWith, Transformable};
+use jagua_rs::geometry::intersection::intersection_area;
+use jagua_rs::geometry::primitives::{Rect, SPolygon};
+use jagua_rs::geometry::DTransformation;
+use jagua_rs::io::ext_repr::{ExtPlacedItem, ExtShape, ExtTransformation};
+use jagua_rs::io::import::import_simple_polygon;
+use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+
+/// Item placed more or fewer times than its demand
+#[derive(Debug, Clone)]
+pub struct DemandViolation {
+    pub item_id: u64,
+    pub demand: u64,
+    pub placed: u64,
+}
+
+/// Two placed items which overlap
+#[derive(Debug, Clone)]
+pub struct CollisionViolation {
+    /// Indices of the items in the solution
+    pub idxs: (usize, usize),
+    pub item_ids: (u64, u64),
+    pub overlap_area: f64,
+}
+
+/// Placed item which sticks out of the container
+#[derive(Debug, Clone)]
+pub struct ContainmentViolation {
+    /// Index of the item in the solution
+    pub idx: usize,
+    pub item_id: u64,
+    pub outside_area: f64,
+}
+
+/// Outcome of [`validate`]
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub n_placed: usize,
+    /// Bounding box of all placed items
+    pub bbox: Option<Rect>,
+    pub demand_violations: Vec<DemandViolation>,
+    pub collisions: Vec<CollisionViolation>,
+    pub containment_violations: Vec<ContainmentViolation>,
+}
+
+impl ValidationReport {
+    pub fn is_feasible(&self) -> bool {
+        self.n_violations() == 0
+    }
+
+    pub fn n_violations(&self) -> usize {
+        self.demand_violations.len() + self.collisions.len() + self.containment_violations.len()
+    }
+
+    /// Side of the smallest square which contains all placed items
+    pub fn bounding_square_side(&self) -> f64 {
+        self.bbox.map_or(0.0, |bbox| f64::max(bbox.width(), bbox.height()))
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} items placed, bounding square side: {:.6}, ", self.n_placed, self.bounding_square_side())?;
+        match self.is_feasible() {
+            true => write!(f, "feasible")?,
+            false => write!(f, "infeasible ({} violations)", self.n_violations())?,
+        }
+        for v in &self.demand_violations {
+            write!(f, "\n  item {} is placed {} times, its demand is {}", v.item_id, v.placed, v.demand)?;
+        }
+        for v in &self.collisions {
+            write!(f, "\n  #{} (item {}) and #{} (item {}) overlap, area: {:.3e}", v.idxs.0, v.item_ids.0, v.idxs.1, v.item_ids.1, v.overlap_area)?;
+        }
+        for v in &self.containment_violations {
+            write!(f, "\n  #{} (item {}) is not inside the container, area outside: {:.3e}", v.idx, v.item_id, v.outside_area)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks a solution against the original shapes of the instance, independently of the optimizer.
+/// Every placed item is rebuilt from its external shape and exported transformation (rotation in degrees, followed by translation).
+/// Overlaps and containment are measured with the exact intersection area of the polygons.
+///
+/// * `container` - Width and height of the rectangular container (anchored at the origin), containment is not checked if `None`.
+/// * `tolerance` - Overlap and outside areas up to this fraction of the area of the (smallest) item involved are ignored.
+pub fn validate(instance: &ExtSPInstance, placed_items: &[ExtPlacedItem], container: Option<(f64, f64)>, tolerance: f64) -> Result<ValidationReport> {
+    let mut shapes = HashMap::new();
+    for item in &instance.items {
+        let shape = original_shape(&item.base.shape).context(format!("could not rebuild the shape of item {}", item.base.id))?;
+        shapes.insert(item.base.id, shape);
+    }
+
+    let mut placed_qtys: HashMap<u64, u64> = HashMap::new();
+    let mut placed_shapes = Vec::with_capacity(placed_items.len());
+    for (idx, pi) in placed_items.iter().enumerate() {
+        let Some(shape) = shapes.get(&pi.item_id) else {
+            bail!("placed item #{idx} refers to item {}, which is not part of the instance", pi.item_id);
+        };
+        placed_shapes.push(placed_shape(shape, &pi.transformation));
+        *placed_qtys.entry(pi.item_id).or_default() += 1;
+    }
+
+    let demand_violations = instance.items.iter()
+        .map(|item| DemandViolation {
+            item_id: item.base.id,
+            demand: item.demand,
+            placed: placed_qtys.get(&item.base.id).copied().unwrap_or(0),
+        })
+        .filter(|v| v.placed != v.demand)
+        .collect();
+
+    let mut collisions = vec![];
+    for (i, s1) in placed_shapes.iter().enumerate() {
+        for (j, s2) in placed_shapes.iter().enumerate().skip(i + 1) {
+            if !s1.bbox.collides_with(&s2.bbox) {
+                continue;
+            }
+            let overlap_area = intersection_area(s1, s2);
+            if overlap_area > tolerance * f64::min(s1.area, s2.area) {
+                collisions.push(CollisionViolation {
+                    idxs: (i, j),
+                    item_ids: (placed_items[i].item_id, placed_items[j].item_id),
+                    overlap_area,
+                });
+            }
+        }
+    }
+
+    let mut containment_violations = vec![];
+    if let Some((width, height)) = container {
+        let container_shape = SPolygon::from(Rect::try_new(0.0, 0.0, width, height)?);
+        for (idx, shape) in placed_shapes.iter().enumerate() {
+            let outside_area = shape.area - intersection_area(shape, &container_shape);
+            if outside_area > tolerance * shape.area {
+                containment_violations.push(ContainmentViolation {
+                    idx,
+                    item_id: placed_items[idx].item_id,
+                    outside_area,
+                });
+            }
+        }
+    }
+
+    Ok(ValidationReport {
+        n_placed: placed_items.len(),
+        bbox: placed_shapes.iter().map(|s| s.bbox).reduce(Rect::bounding_rect),
+        demand_violations,
+        collisions,
+        containment_violations,
+    })
+}
+
+/// Original shape of an item, as defined in the instance
+pub fn original_shape(shape: &ExtShape) -> Result<SPolygon> {
+    match shape {
+        ExtShape::Rectangle { x_min, y_min, width, height } => {
+            Ok(SPolygon::from(Rect::try_new(*x_min, *y_min, x_min + width, y_min + height)?))
+        }
+        ExtShape::SimplePolygon(sp) => import_simple_polygon(sp),
+        //holes are ignored, as they are by the optimizer
+        ExtShape::Polygon(p) => import_simple_polygon(&p.outer),
+        ExtShape::MultiPolygon(_) => bail!("multipolygons are not supported"),
+    }
+}
+
+/// Original shape of an item with an exported transformation applied
+pub fn placed_shape(original: &SPolygon, transformation: &ExtTransformation) -> SPolygon {
+    let dt = DTransformation::new(transformation.rotation.to_radians(), transformation.translation);
+    original.transform_clone(&dt.compose())
+}
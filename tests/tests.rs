@@ -17,12 +17,87 @@ mod integration_tests {
     use std::time::Duration;
     use test_case::test_case;
     use sparrow::util::listener::DummySolListener;
+    use jagua_rs::geometry::closest_rotation_in_intervals;
+    use sparrow::sample::low_discrepancy::{LowDiscrepancySequence, SobolSequence};
+    use sparrow::sample::sampler::ContainerSamplerMix;
+    use sparrow::sample::coord_descent::CDConfig;
+    use sparrow::sample::refiner::{RefineStrategy, Refiner};
+    use sparrow::eval::sample_eval::{SampleEval, SampleEvaluator};
+    use jagua_rs::geometry::DTransformation;
+    use float_cmp::assert_approx_eq;
+    use sparrow::util::early_terminator::{EarlyTerminationConfig, EarlyTerminator, StagnationLimit, Target};
+    use sparrow::util::terminator::KillReason;
+    use sparrow::util::validator::validate;
+    use sparrow::util::rounding::{round_output, round_to};
+    use sparrow::util::io::SPOutput;
+    use sparrow::optimizer::separator::ContainerMode;
+    use jagua_rs::io::ext_repr::ExtLayout;
+    use jagua_rs::probs::spp::io::ext_repr::ExtSPSolution;
+    use sparrow::consts::DEFAULT_VALIDATION_TOLERANCE;
+    use jagua_rs::io::ext_repr::{ExtPlacedItem, ExtTransformation};
+    use jagua_rs::probs::spp::io::ext_repr::ExtSPInstance;
+    use jagua_rs::io::export::export_layout_snapshot;
+    use std::time::Instant;
+    use jagua_rs::geometry::primitives::{Rect, SPolygon};
+    use sparrow::quantify::quantify_collision_poly_container;
+    use sparrow::optimizer::separator::{accept_move, AcceptanceCriterion};
+    use sparrow::util::listener::{ReportType, SepProgress, SolutionListener};
+    use jagua_rs::probs::spp::entities::{SPInstance, SPSolution};
+    use sparrow::consts::{ANNEALING_T_DECAY, ANNEALING_T_INIT};
+    use jagua_rs::probs::spp::entities::{SPPlacement, SPProblem};
+    use jagua_rs::entities::{Instance, Layout};
+    use sparrow::sample::uniform_sampler::convert_sample_to_closest_feasible;
+    use sparrow::optimizer::ruin_recreate::{ruin_and_recreate, RuinRecreateConfig, RuinRegion};
+    use sparrow::consts::DEFAULT_RUIN_RECREATE_CONFIG;
+    use sparrow::util::assertions::tracker_matches_layout;
+    use itertools::Itertools;
+    use sparrow::optimizer::split::{SplitAxis, SplitConfig, SplitScore, SplitSelector};
+    use std::ops::Range;
+    use sparrow::sample::budget::SampleBudgets;
+    use sparrow::quantify::tracker::{CollisionTracker, WeightConfig, WeightSchedule, WeightSelection};
+    use sparrow::consts::DEFAULT_WEIGHT_CONFIG;
+    use sparrow::sample::search::{SampleConfig, SampleSource, SearchStats};
+    use sparrow::consts::DEFAULT_ADAPTIVE_BUDGET_CONFIG;
+    use sparrow::util::event_stream::EventStreamListener;
+    use sparrow::optimizer::worker::SepStats;
+    use std::sync::{Arc, Mutex};
+    use sparrow::util::live_server::LiveServer;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use sparrow::util::svg_exporter::SvgExporter;
+    use sparrow::util::run_controller::{ControlCommand, RunController};
+    use sparrow::util::heatmap::{HeatmapMetric, HeatmapOptions};
+    use sparrow::util::replay::{html_player, read_recording, render_frames, ReplayRecorder};
+    use sparrow::sample::rotation::{RotationConfig, RotationLearningConfig, RotationModel};
+    use sparrow::consts::{DEFAULT_ROTATION_CONFIG, DEFAULT_ROTATION_LEARNING_CONFIG};
+    use sparrow::optimizer::compress::attempt_to_polish;
+    use sparrow::optimizer::polish::{polish, PolishConfig};
+    use sparrow::consts::DEFAULT_POLISH_CONFIG;
 
     const EXPLORE_TIMEOUT: Duration = Duration::from_secs(10);
     const COMPRESS_TIMEOUT: Duration = Duration::from_secs(10);
     const INSTANCE_BASE_PATH: &str = "data/input";
     const RNG_SEED: Option<usize> = Some(0); // fix seed for reproducibility
 
+    fn import_instance(path: &str) -> Result<SPInstance> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let json_instance = io::read_spp_instance_json(Path::new(&format!("{INSTANCE_BASE_PATH}/{path}")))?;
+        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+        jagua_rs::probs::spp::io::import(&importer, &json_instance)
+    }
+
+    /// Keeps the last progress report of the separator
+    #[derive(Default)]
+    struct ProgressListener(Option<SepProgress>);
+
+    impl SolutionListener for ProgressListener {
+        fn report(&mut self, _report: ReportType, _solution: &SPSolution, _instance: &SPInstance) {}
+
+        fn report_progress(&mut self, progress: &SepProgress) {
+            self.0 = Some(*progress);
+        }
+    }
+
     #[test_case("swim.json"; "swim")]
     #[test_case("shirts.json"; "shirts")]
     #[test_case("trousers.json"; "trousers")]
@@ -62,4 +137,940 @@ mod integration_tests {
         compression_phase(&instance, &mut separator, final_explore_sol, &mut sol_listener, &terminator, &config.cmpr_cfg);
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Loss of a 2x2 square item at (x_min, y_min) against a 10x10 container,
+    /// which is `2 * sqrt(overlap) * sqrt(item area)`, where overlap is the area outside the container (+ 0.0001 * item area)
+    #[test_case(1.0, 1.0, 0.0004; "fully inside")]
+    #[test_case(-1.0, 1.0, 2.0004; "partly outside")]
+    #[test_case(9.5, 9.5, 3.7504; "corner outside")]
+    #[test_case(20.0, 1.0, 4.0 + f64::sqrt(16.0 * 16.0 + 3.0 * 3.0); "fully outside")]
+    fn container_collision_loss(x_min: f64, y_min: f64, expected_overlap: f64) -> Result<()> {
+        let container = SPolygon::from(Rect::try_new(0.0, 0.0, 10.0, 10.0)?);
+        let mut item = SPolygon::from(Rect::try_new(x_min, y_min, x_min + 2.0, y_min + 2.0)?);
+        item.generate_surrogate(DEFAULT_SPARROW_CONFIG.cde_config.item_surrogate_config)?;
+
+        let loss = quantify_collision_poly_container(&item, &container);
+        assert_approx_eq!(f64, loss, 2.0 * expected_overlap.sqrt() * 2.0, epsilon = 1e-9);
+        Ok(())
+    }
+
+    const ROTATION_INTERVALS: [(f64, f64); 2] = [(-5.0, 5.0), (175.0, 185.0)];
+
+    #[test_case(0.0, 0.0; "inside")]
+    #[test_case(360.0, 0.0; "inside, wrapped")]
+    #[test_case(8.0, 5.0; "above first interval")]
+    #[test_case(-10.0, -5.0; "below first interval")]
+    #[test_case(100.0, 175.0; "between intervals")]
+    #[test_case(260.0, 185.0; "after second interval")]
+    #[test_case(300.0, -5.0; "wrapped to start of first interval")]
+    fn rotation_clamped_to_intervals(r: f64, expected: f64) {
+        let intervals = ROTATION_INTERVALS.map(|(s, e)| (s.to_radians(), e.to_radians()));
+        let clamped = closest_rotation_in_intervals(r.to_radians(), &intervals);
+        assert_approx_eq!(f64, clamped, expected.to_radians(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn sobol_sequence_is_stratified() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        let mut sobol = SobolSequence::new(&mut rng);
+        let points = (0..64).map(|_| sobol.next_point()).collect::<Vec<_>>();
+
+        //the first 64 points of every dimension fall exactly 8 times in each of 8 equal bins
+        for d in 0..3 {
+            let mut bins = [0; 8];
+            for p in points.iter() {
+                assert!((0.0..1.0).contains(&p[d]));
+                bins[(p[d] * 8.0) as usize] += 1;
+            }
+            assert_eq!(bins, [8; 8], "dimension {d} is not stratified");
+        }
+    }
+
+    #[test_case(ContainerSamplerMix::UNIFORM, 50, [50, 0, 0, 0]; "uniform")]
+    #[test_case(ContainerSamplerMix { uniform: 1.0, halton: 1.0, sobol: 0.0, free_space: 1.0 }, 50, [17, 16, 0, 17]; "thirds")]
+    #[test_case(ContainerSamplerMix { uniform: 0.0, halton: 0.0, sobol: 2.0, free_space: 0.0 }, 7, [0, 0, 7, 0]; "sobol only")]
+    fn container_sampler_mix_split(mix: ContainerSamplerMix, n_samples: usize, expected: [usize; 4]) {
+        assert_eq!(mix.split(n_samples), expected);
+    }
+
+    /// Evaluates samples by their squared distance to a fixed optimum
+    struct BowlEvaluator {
+        optimum: (f64, f64, f64),
+        n_evals: usize,
+    }
+
+    impl SampleEvaluator for BowlEvaluator {
+        fn evaluate_sample(&mut self, dt: DTransformation, _upper_bound: Option<SampleEval>) -> SampleEval {
+            self.n_evals += 1;
+            let (x, y) = dt.translation();
+            let (ox, oy, or) = self.optimum;
+            let loss = (x - ox).powi(2) + (y - oy).powi(2) + (dt.rotation() - or).powi(2);
+            SampleEval::Collision { loss }
+        }
+
+        fn n_evals(&self) -> usize {
+            self.n_evals
+        }
+    }
+
+    #[test_case(RefineStrategy::CoordinateDescent; "coordinate descent")]
+    #[test_case(RefineStrategy::PatternSearch; "pattern search")]
+    #[test_case(RefineStrategy::NelderMead; "nelder mead")]
+    #[test_case(RefineStrategy::SlideToContact; "slide to contact")]
+    fn refiner_converges_to_optimum(refiner: RefineStrategy) {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        let mut evaluator = BowlEvaluator { optimum: (1.3, -0.7, 0.2), n_evals: 0 };
+        let config = CDConfig {
+            t_step_init: 0.5,
+            t_step_limit: 1e-4,
+            r_step_init: 0.1,
+            r_step_limit: 1e-4,
+            wiggle: true,
+            r_intervals: None,
+        };
+        let init_dt = DTransformation::new(0.0, (0.0, 0.0));
+        let init = (init_dt, evaluator.evaluate_sample(init_dt, None));
+
+        let (dt, _) = refiner.refine(init, &mut evaluator, config, &mut rng);
+        let (x, y) = dt.translation();
+        assert_approx_eq!(f64, x, 1.3, epsilon = 1e-2);
+        assert_approx_eq!(f64, y, -0.7, epsilon = 1e-2);
+        assert_approx_eq!(f64, dt.rotation(), 0.2, epsilon = 1e-2);
+    }
+
+    #[test_case(Some(Target::Size(f64::INFINITY)), StagnationLimit::default(), KillReason::TargetReached(Target::Size(f64::INFINITY)); "target size")]
+    #[test_case(Some(Target::Density(0.0)), StagnationLimit::default(), KillReason::TargetReached(Target::Density(0.0)); "target density")]
+    #[test_case(None, StagnationLimit { time: Some(Duration::ZERO), attempts: None }, KillReason::StagnationTime(Duration::ZERO); "stagnation time")]
+    fn early_terminator_stops_exploration(target: Option<Target>, stagnation: StagnationLimit, expected: KillReason) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let json_instance = io::read_spp_instance_json(Path::new(&format!("{INSTANCE_BASE_PATH}/swim.json")))?;
+        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+        let instance = jagua_rs::probs::spp::io::import(&importer, &json_instance)?;
+        let rng = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let early_config = EarlyTerminationConfig { target, expl_stagnation: stagnation, cmpr_stagnation: stagnation };
+        let mut terminator = EarlyTerminator::new(BasicTerminator::new(), early_config);
+        let mut sol_listener = terminator.listener();
+        terminator.new_timeout(EXPLORE_TIMEOUT);
+
+        let builder = LBFBuilder::new(instance.clone(), rng, LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, config.expl_cfg.separator_config);
+        let sols = exploration_phase(&instance, &mut separator, &mut sol_listener, &terminator, &config.expl_cfg);
+
+        //the initial solution already satisfies the condition, so no separation should be attempted
+        assert_eq!(sols.len(), 1);
+        assert_eq!(terminator.kill_reason(), Some(expected));
+        Ok(())
+    }
+
+    #[test_case(&[(0.0, 0.0), (2.0, 0.0)], 2, (0, 0, 0); "touching")]
+    #[test_case(&[(0.0, 0.0), (1.0, 0.0)], 2, (0, 1, 0); "overlapping")]
+    #[test_case(&[(0.0, 0.0), (3.0, 0.0)], 2, (0, 0, 1); "outside container")]
+    #[test_case(&[(0.0, 0.0)], 2, (1, 0, 0); "missing item")]
+    fn validator_detects_violations(translations: &[(f64, f64)], demand: u64, expected: (usize, usize, usize)) -> Result<()> {
+        let instance: ExtSPInstance = serde_json::from_str(&format!(r#"{{"name": "squares", "strip_height": 2.0, "items": [
+            {{"id": 0, "demand": {demand}, "shape": {{"type": "rectangle", "data": {{"x_min": 0.0, "y_min": 0.0, "width": 2.0, "height": 2.0}}}}}}
+        ]}}"#))?;
+        let placed_items = translations.iter().enumerate()
+            .map(|(copy_idx, &translation)| ExtPlacedItem { item_id: 0, copy_idx: copy_idx as u64, transformation: ExtTransformation { rotation: 0.0, translation } })
+            .collect::<Vec<_>>();
+
+        let report = validate(&instance, &placed_items, Some((4.0, 2.0)), DEFAULT_VALIDATION_TOLERANCE)?;
+        assert_eq!((report.demand_violations.len(), report.collisions.len(), report.containment_violations.len()), expected);
+        assert_eq!(report.is_feasible(), expected == (0, 0, 0));
+        Ok(())
+    }
+
+    #[test_case(ContainerMode::Strip, &[(0.00004, 0.0), (1.99994, 0.0)], 1; "strip, pushed apart")]
+    #[test_case(ContainerMode::Square, &[(0.0, 0.0), (2.00004, 2.00006)], 1; "square, regrown")]
+    #[test_case(ContainerMode::Strip, &[(0.0, 0.0), (2.00004, 0.0)], 0; "strip, unaffected")]
+    fn rounding_keeps_solution_feasible(container: ContainerMode, translations: &[(f64, f64)], n_violations: usize) -> Result<()> {
+        let instance: ExtSPInstance = serde_json::from_str(r#"{"name": "squares", "strip_height": 4.0, "items": [
+            {"id": 0, "demand": 2, "shape": {"type": "rectangle", "data": {"x_min": 0.0, "y_min": 0.0, "width": 2.0, "height": 2.0}}}
+        ]}"#)?;
+        let placed_items = translations.iter().enumerate()
+            .map(|(copy_idx, &translation)| ExtPlacedItem { item_id: 0, copy_idx: copy_idx as u64, transformation: ExtTransformation { rotation: 0.0, translation } })
+            .collect::<Vec<_>>();
+        let output = SPOutput {
+            instance,
+            solution: ExtSPSolution {
+                strip_width: 4.00004,
+                layout: ExtLayout { container_id: 0, placed_items, density: 0.5 },
+                density: 0.5,
+                run_time_sec: 0,
+            },
+            rotation_histograms: vec![],
+        };
+
+        let (rounded, report) = round_output(&output, container, 4, DEFAULT_VALIDATION_TOLERANCE)?;
+        assert_eq!(report.n_violations, n_violations);
+        assert!(report.size_lost() >= 0.0);
+        let container_size = (rounded.solution.strip_width, rounded.instance.strip_height);
+        assert!(validate(&rounded.instance, &rounded.solution.layout.placed_items, Some(container_size), DEFAULT_VALIDATION_TOLERANCE)?.is_feasible());
+        for pi in &rounded.solution.layout.placed_items {
+            let (x, y) = pi.transformation.translation;
+            assert_eq!((round_to(x, 4), round_to(y, 4)), (x, y));
+        }
+        Ok(())
+    }
+
+    #[test_case("swim.json"; "swim")]
+    #[test_case("shirts.json"; "shirts")]
+    fn copy_ids_are_stable(path: &str) -> Result<()> {
+        let config = DEFAULT_SPARROW_CONFIG;
+        let json_instance = io::read_spp_instance_json(Path::new(&format!("{INSTANCE_BASE_PATH}/{path}")))?;
+        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+        let instance = jagua_rs::probs::spp::io::import(&importer, &json_instance)?;
+        let rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        let epoch = Instant::now();
+
+        let mut terminator = BasicTerminator::new();
+        terminator.new_timeout(Duration::from_secs(2));
+
+        let builder = LBFBuilder::new(instance.clone(), rng, LBF_SAMPLE_CONFIG).construct();
+        let initial_sol = jagua_rs::probs::spp::io::export(&instance, &builder.prob.save(), epoch);
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, config.expl_cfg.separator_config);
+        let sols = exploration_phase(&instance, &mut separator, &mut DummySolListener, &terminator, &config.expl_cfg);
+        let final_sol = jagua_rs::probs::spp::io::export(&instance, sols.last().expect("no solutions found during exploration"), epoch);
+
+        //every copy of every item is exported exactly once, in the same order for every solution
+        let ids = |layout: &ExtLayout| layout.placed_items.iter().map(|pi| (pi.item_id, pi.copy_idx)).collect::<Vec<_>>();
+        let expected = instance.items.iter()
+            .flat_map(|(item, qty)| (0..*qty as u64).map(|copy_idx| (item.id as u64, copy_idx)))
+            .collect::<Vec<_>>();
+        assert_eq!(ids(&initial_sol.layout), expected);
+        assert_eq!(ids(&final_sol.layout), expected);
+
+        //and the ids survive a round trip through the external representation
+        let reimported = io::import_layout(&instance, &final_sol)?;
+        assert_eq!(ids(&export_layout_snapshot(&reimported.save(), &instance)), expected);
+        Ok(())
+    }
+
+    #[test_case(AcceptanceCriterion::Greedy, &[0.0, 0.0, 0.0]; "greedy")]
+    #[test_case(AcceptanceCriterion::Annealing { t_init: 0.1, t_decay: 0.5 }, &[0.1, 0.05, 0.025]; "annealing")]
+    fn acceptance_temperature_schedule(acceptance: AcceptanceCriterion, expected: &[f64]) {
+        for (n_iter, &t) in expected.iter().enumerate() {
+            assert_approx_eq!(f64, acceptance.temperature(n_iter), t, epsilon = 1e-12);
+        }
+    }
+
+    /// Share of 10000 moves from a weighted loss of 1.0 to `new_w_l` which is accepted
+    #[test_case(0.5, 0.0, 1.0; "greedy, downhill")]
+    #[test_case(1.0, 0.0, 1.0; "greedy, equal")]
+    #[test_case(1.5, 0.0, 0.0; "greedy, uphill")]
+    #[test_case(0.5, 0.1, 1.0; "annealing, downhill")]
+    #[test_case(1.1, 0.1, f64::exp(-1.0); "annealing, uphill")]
+    #[test_case(2.0, 0.1, f64::exp(-10.0); "annealing, far uphill")]
+    fn acceptance_of_moves(new_w_l: f64, temperature: f64, expected_ratio: f64) {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        let n_accepted = (0..10000).filter(|_| accept_move(1.0, new_w_l, temperature, &mut rng)).count();
+        assert_approx_eq!(f64, n_accepted as f64 / 10000.0, expected_ratio, epsilon = 0.02);
+    }
+
+    #[test_case(AcceptanceCriterion::Greedy; "greedy")]
+    #[test_case(AcceptanceCriterion::Annealing { t_init: ANNEALING_T_INIT, t_decay: ANNEALING_T_DECAY }; "annealing")]
+    fn separation_with_acceptance(acceptance: AcceptanceCriterion) -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let mut sep_config = DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config;
+        sep_config.acceptance = acceptance;
+        sep_config.container = ContainerMode::Strip;
+
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, sep_config);
+        separator.change_size(separator.prob.strip_width() * 0.9, None);
+        let initial_loss = separator.ct.get_total_loss();
+
+        let mut terminator = BasicTerminator::new();
+        terminator.new_timeout(Duration::from_secs(5));
+        let mut listener = ProgressListener::default();
+        let (_, ct) = separator.separate(&terminator, &mut listener);
+
+        //the best solution of the separation is returned, whichever moves were accepted along the way
+        assert!(ct.get_total_loss() <= initial_loss);
+        let stats = listener.0.expect("no progress reported").stats;
+        match acceptance {
+            AcceptanceCriterion::Greedy => assert_eq!(stats.total_uphill_moves, 0),
+            AcceptanceCriterion::Annealing { .. } => assert!(stats.total_uphill_moves > 0),
+        }
+        Ok(())
+    }
+
+    /// Two items of similar size (each other's only swap partner) with rotations restricted to intervals, and two smaller items,
+    /// all taller than the strip within their allowed rotations, so every item is still colliding when its turn comes.
+    /// Every item gets its turn, also when its swap partner was relocated before it, and all rotations remain allowed.
+    #[test]
+    fn swap_keeps_partner_turn_and_allowed_rotations() -> Result<()> {
+        let ext_instance: ExtSPInstance = serde_json::from_str(r#"{"name": "swap", "strip_height": 1.0, "items": [
+            {"id": 0, "demand": 1, "allowed_rotation_intervals": [[-5.0, 5.0]], "shape": {"type": "rectangle", "data": {"x_min": 0.0, "y_min": 0.0, "width": 2.0, "height": 1.1}}},
+            {"id": 1, "demand": 1, "allowed_rotation_intervals": [[85.0, 95.0]], "shape": {"type": "rectangle", "data": {"x_min": 0.0, "y_min": 0.0, "width": 1.1, "height": 2.2}}},
+            {"id": 2, "demand": 2, "shape": {"type": "rectangle", "data": {"x_min": 0.0, "y_min": 0.0, "width": 1.05, "height": 1.05}}}
+        ]}"#)?;
+        let config = DEFAULT_SPARROW_CONFIG;
+        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+        let instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+
+        let mut prob = SPProblem::new(instance.clone());
+        prob.change_strip_width(3.0);
+        for (item_id, copy_idx, translation) in [(0, 0, (1.0, 0.5)), (1, 0, (1.5, 0.5)), (2, 0, (2.0, 0.5)), (2, 1, (2.4, 0.5))] {
+            prob.place_item(SPPlacement { item_id, copy_idx, d_transf: DTransformation::new(0.0, translation) });
+        }
+
+        let mut sep_config = config.expl_cfg.separator_config;
+        sep_config.swap_ratio = 1.0;
+        sep_config.container = ContainerMode::Strip;
+
+        let mut n_swaps = 0;
+        for seed in 0..16 {
+            let mut separator = Separator::new(instance.clone(), prob.clone(), Xoshiro256PlusPlus::seed_from_u64(seed), sep_config);
+            let worker = &mut separator.workers[0];
+            let stats = worker.move_items();
+
+            //every item either moved or swapped
+            assert_eq!(stats.total_moves + stats.total_swaps, 4, "seed {seed}");
+            for pi in worker.prob.layout.placed_items.values() {
+                let feasible_dt = convert_sample_to_closest_feasible(pi.d_transf, instance.item(pi.item_id));
+                assert_approx_eq!(f64, feasible_dt.rotation(), pi.d_transf.rotation(), epsilon = 1e-12);
+            }
+            n_swaps += stats.total_swaps;
+        }
+        //whether a swap is accepted depends on the samples, but some seeds must exercise the swap path
+        assert!(n_swaps > 0);
+        Ok(())
+    }
+
+    /// After ruining a region of a colliding layout, all items are re-inserted with their copy indices
+    /// and the separator's tracker still matches the layout
+    #[test_case(RuinRegion::Disc; "disc")]
+    #[test_case(RuinRegion::Band; "band")]
+    fn ruin_and_recreate_reinserts_all_items(region: RuinRegion) -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let sep_config = DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, sep_config);
+        separator.change_size(separator.prob.strip_width() * 0.9, None);
+
+        let copies = |sep: &Separator| sep.prob.layout.placed_items.values()
+            .map(|pi| (pi.item_id, pi.copy_idx))
+            .sorted()
+            .collect_vec();
+        let copies_before = copies(&separator);
+
+        //the sum of all colliding pairs and container collisions is the total loss
+        let pair_loss = separator.ct.colliding_pairs().iter().map(|(_, _, loss)| loss).sum::<f64>();
+        let container_loss = separator.prob.layout.placed_items.keys().map(|pk| separator.ct.get_container_loss(pk)).sum::<f64>();
+        assert_approx_eq!(f64, pair_loss + container_loss, separator.ct.get_total_loss(), epsilon = 1e-9 * separator.ct.get_total_loss());
+
+        let config = RuinRecreateConfig { region, ..DEFAULT_RUIN_RECREATE_CONFIG };
+        let n_ruined = ruin_and_recreate(&mut separator, &config);
+
+        assert!(n_ruined > 0);
+        assert_eq!(copies(&separator), copies_before);
+        assert!(tracker_matches_layout(&separator.ct, &separator.prob.layout));
+        Ok(())
+    }
+
+    /// Without any collisions, there is no region to ruin and the layout is left untouched
+    #[test]
+    fn ruin_and_recreate_without_collisions() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let sep_config = DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, sep_config);
+        let placements_before = separator.prob.layout.placed_items.iter()
+            .map(|(pk, pi)| (pk, pi.d_transf))
+            .collect_vec();
+
+        assert!(separator.ct.colliding_pairs().is_empty());
+        assert_eq!(ruin_and_recreate(&mut separator, &DEFAULT_RUIN_RECREATE_CONFIG), 0);
+        for (pk, d_transf) in placements_before {
+            assert_eq!(separator.prob.layout.placed_items[pk].d_transf, d_transf);
+        }
+        Ok(())
+    }
+
+    /// Polishing a loose layout into a 1% smaller container succeeds with a smaller feasible layout.
+    /// The layout is loosened by spreading the LBF layout over a 10% larger square, leaving gaps between all items.
+    #[test]
+    fn polish_attempt_compresses_loose_layout() -> Result<()> {
+        let r_shrink = 0.01;
+        let instance = import_instance("swim.json")?;
+        let sep_config = DEFAULT_SPARROW_CONFIG.cmpr_cfg.separator_config;
+        assert_eq!(sep_config.container, ContainerMode::Square);
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+
+        let mut prob = builder.prob;
+        prob.change_square_size(prob.strip_width() * 1.1);
+        //scale the positions of all items away from the origin
+        let spread = prob.layout.placed_items.iter()
+            .map(|(pk, pi)| (pk, pi.d_transf, pi.shape.centroid()))
+            .collect_vec();
+        for (pk, d_transf, centroid) in spread {
+            let placement = prob.remove_item(pk);
+            let (t_x, t_y) = d_transf.translation();
+            prob.place_item(SPPlacement { d_transf: DTransformation::new(d_transf.rotation(), (t_x + 0.1 * centroid.0, t_y + 0.1 * centroid.1)), ..placement });
+        }
+        assert!(prob.layout.is_feasible());
+
+        let mut separator = Separator::new(builder.instance, prob, builder.rng, sep_config);
+        let init = separator.prob.save();
+
+        let mut terminator = BasicTerminator::new();
+        terminator.new_timeout(Duration::from_secs(5));
+        let sol = attempt_to_polish(&mut separator, &init, r_shrink, &DEFAULT_POLISH_CONFIG, &terminator, &mut DummySolListener)
+            .expect("polishing a loose layout failed");
+        assert_approx_eq!(f64, sol.strip_width(), init.strip_width() * (1.0 - r_shrink));
+        assert_eq!(sol.layout_snapshot.placed_items.len(), init.layout_snapshot.placed_items.len());
+        assert_eq!(separator.ct.get_total_loss(), 0.0);
+        Ok(())
+    }
+
+    /// The gradient descent of polishing lowers the loss of the layout in which all positions are only scaled towards the origin
+    #[test_case(0.01; "1% smaller")]
+    #[test_case(0.05; "5% smaller")]
+    fn polish_lowers_loss_of_shrunk_layout(r_shrink: f64) -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let sep_config = DEFAULT_SPARROW_CONFIG.cmpr_cfg.separator_config;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, sep_config);
+        let init = separator.prob.save();
+        let new_size = init.strip_width() * (1.0 - r_shrink);
+
+        //without any iterations, polishing only scales the positions
+        let scale_only = PolishConfig { n_iterations: 0, ..DEFAULT_POLISH_CONFIG };
+        let mut polished_loss = |config: &PolishConfig| {
+            separator.change_size(init.strip_width(), None);
+            separator.rollback(&init, None);
+            polish(&mut separator, new_size, config);
+            assert_approx_eq!(f64, separator.prob.strip_width(), new_size);
+            separator.ct.get_total_loss()
+        };
+        let shrunk_loss = polished_loss(&scale_only);
+        let descended_loss = polished_loss(&DEFAULT_POLISH_CONFIG);
+
+        assert!(shrunk_loss > 0.0);
+        assert!(descended_loss < shrunk_loss, "polished loss {descended_loss} is not below shrunk loss {shrunk_loss}");
+        Ok(())
+    }
+
+    /// With polishing enabled, the compression phase lowers the size of a feasible layout or leaves it unchanged
+    #[test]
+    fn compression_with_polish_never_grows() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let mut cmpr_config = DEFAULT_SPARROW_CONFIG.cmpr_cfg;
+        cmpr_config.polish = Some(DEFAULT_POLISH_CONFIG);
+        cmpr_config.time_limit = Duration::from_secs(5);
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, cmpr_config.separator_config);
+        let init = separator.prob.save();
+
+        let mut terminator = BasicTerminator::new();
+        terminator.new_timeout(cmpr_config.time_limit);
+        let sol = compression_phase(&instance, &mut separator, &init, &mut DummySolListener, &terminator, &cmpr_config);
+
+        assert!(sol.strip_width() <= init.strip_width());
+        separator.change_size(sol.strip_width(), None);
+        separator.rollback(&sol, None);
+        assert_eq!(separator.ct.get_total_loss(), 0.0);
+        Ok(())
+    }
+
+    /// Places rectangles of (width, height) at their (centroid) positions in a strip of 10x2
+    fn rectangle_layout(rects: &[(f64, f64, (f64, f64))]) -> Result<SPProblem> {
+        let items = rects.iter().enumerate()
+            .map(|(id, (w, h, _))| format!(r#"{{"id": {id}, "demand": 1, "shape": {{"type": "rectangle", "data": {{"x_min": 0.0, "y_min": 0.0, "width": {w}, "height": {h}}}}}}}"#))
+            .join(", ");
+        let ext_instance: ExtSPInstance = serde_json::from_str(&format!(r#"{{"name": "rects", "strip_height": 2.0, "items": [{items}]}}"#))?;
+        let config = DEFAULT_SPARROW_CONFIG;
+        let importer = Importer::new(config.cde_config, config.poly_simpl_tolerance, config.min_item_separation, config.narrow_concavity_cutoff_ratio);
+        let instance = jagua_rs::probs::spp::io::import(&importer, &ext_instance)?;
+
+        let mut prob = SPProblem::new(instance);
+        prob.change_strip_width(10.0);
+        for (item_id, (_, _, translation)) in rects.iter().enumerate() {
+            prob.place_item(SPPlacement { item_id, copy_idx: 0, d_transf: DTransformation::new(0.0, *translation) });
+        }
+        Ok(prob)
+    }
+
+    /// Without any gaps, a selection without randomness picks the best ranked line:
+    /// the left half is crossed by a single large item, the right half by two small ones
+    #[test_case(SplitScore::NumberOfItems, 0.0..5.0; "fewest items")]
+    #[test_case(SplitScore::ItemArea, 5.0..10.0; "least item area")]
+    fn split_selection_ranks_candidates(score: SplitScore, expected: Range<f64>) -> Result<()> {
+        let prob = rectangle_layout(&[(5.1, 1.9, (2.5, 1.0)), (5.1, 0.5, (7.5, 0.5)), (5.1, 0.5, (7.5, 1.5))])?;
+        let selector = SplitSelector::new(SplitConfig { n_candidates: 32, score, selection_stddev: 0.0 });
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        for _ in 0..10 {
+            let pos = selector.select(&prob.layout, SplitAxis::Vertical, &mut rng).expect("no split selected");
+            assert!(expected.contains(&pos), "split at {pos} not in {expected:?}");
+        }
+        Ok(())
+    }
+
+    /// A line which crosses no items is always preferred, regardless of the randomness of the ranked selection
+    #[test]
+    fn split_selection_prefers_gaps() -> Result<()> {
+        let prob = rectangle_layout(&[(4.0, 1.9, (2.0, 1.0)), (4.0, 1.9, (8.0, 1.0))])?;
+        let selector = SplitSelector::new(SplitConfig { n_candidates: 32, score: SplitScore::NumberOfItems, selection_stddev: 1.0 });
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        for _ in 0..10 {
+            let pos = selector.select(&prob.layout, SplitAxis::Vertical, &mut rng).expect("no split selected");
+            assert!((4.0..6.0).contains(&pos), "split at {pos} is not in the gap");
+        }
+        Ok(())
+    }
+
+    /// Configs which do not allow a selection yield no split instead of panicking
+    #[test_case(0, 0.25; "no candidates")]
+    #[test_case(16, -0.25; "negative stddev")]
+    #[test_case(16, f64::NAN; "nan stddev")]
+    fn split_selection_with_invalid_config(n_candidates: usize, selection_stddev: f64) -> Result<()> {
+        let prob = rectangle_layout(&[(5.1, 1.9, (2.5, 1.0)), (5.1, 1.9, (7.5, 1.0))])?;
+        let selector = SplitSelector::new(SplitConfig { n_candidates, score: SplitScore::ItemArea, selection_stddev });
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        assert_eq!(selector.select(&prob.layout, SplitAxis::Vertical, &mut rng), None);
+        Ok(())
+    }
+
+    /// The configuration of a single item and the learned distributions are carried over to the model of the next phase,
+    /// a distribution only if the item divides the circle into the same number of bins in both
+    #[test]
+    fn rotation_model_adopts_item_configs_and_histograms() -> Result<()> {
+        let instance = rectangle_layout(&[(1.0, 1.9, (1.0, 1.0)), (2.0, 0.9, (4.0, 1.0)), (0.5, 0.5, (7.0, 1.0))])?.instance;
+        let learning_config = RotationConfig { learning: Some(DEFAULT_ROTATION_LEARNING_CONFIG), ..DEFAULT_ROTATION_CONFIG };
+        let item_config = RotationConfig { n_samples: 4, learning: Some(RotationLearningConfig { n_bins: 8, uniform_ratio: 0.0 }), ..DEFAULT_ROTATION_CONFIG };
+
+        let mut expl_model = RotationModel::new(instance.items.len(), learning_config);
+        expl_model.set_item_config(1, item_config);
+        for (item_id, rotation) in [(0, 0.1), (0, 0.2), (1, 3.0), (1, -1.0)] {
+            expl_model.record(item_id, rotation);
+        }
+
+        let mut cmpr_model = RotationModel::new(instance.items.len(), learning_config);
+        cmpr_model.adopt(&expl_model);
+
+        assert_eq!(cmpr_model.config(1).n_samples, 4);
+        assert_eq!(cmpr_model.sample_rotations(instance.item(1), &mut Xoshiro256PlusPlus::seed_from_u64(0)).len(), 4);
+        assert_eq!(cmpr_model.config(0).n_samples, DEFAULT_ROTATION_CONFIG.n_samples);
+        for item_id in 0..instance.items.len() {
+            assert_eq!(cmpr_model.histogram(item_id), expl_model.histogram(item_id));
+        }
+
+        //only items with recorded rotations are included in the output
+        let histograms = cmpr_model.learned_histograms();
+        assert_eq!(histograms.iter().map(|h| h.item_id).collect_vec(), vec![0, 1]);
+        assert_eq!(histograms[1].counts.iter().sum::<usize>(), 2);
+        assert_eq!(histograms[1].bin_width, 45.0);
+        Ok(())
+    }
+
+    /// Learned distributions are written to the JSON output and read back, the field is left out if nothing was learned
+    #[test]
+    fn rotation_histograms_in_output() -> Result<()> {
+        let prob = rectangle_layout(&[(1.0, 1.9, (1.0, 1.0)), (2.0, 0.9, (4.0, 1.0))])?;
+        let mut rot_model = RotationModel::new(prob.instance.items.len(), RotationConfig { learning: Some(DEFAULT_ROTATION_LEARNING_CONFIG), ..DEFAULT_ROTATION_CONFIG });
+        rot_model.record(1, f64::to_radians(95.0));
+
+        let ext_instance: ExtSPInstance = serde_json::from_str(r#"{"name": "empty", "strip_height": 2.0, "items": []}"#)?;
+        let solution = ExtSPSolution {
+            strip_width: 10.0,
+            layout: ExtLayout { container_id: 0, placed_items: vec![], density: 0.0 },
+            density: 0.0,
+            run_time_sec: 0,
+        };
+        let output = SPOutput { instance: ext_instance.clone(), solution: solution.clone(), rotation_histograms: rot_model.learned_histograms() };
+        let json = serde_json::to_value(&output)?;
+        assert_eq!(json["rotation_histograms"][0]["item_id"], 1);
+        assert_eq!(json["rotation_histograms"][0]["counts"][9], 1);
+        let read: SPOutput = serde_json::from_value(json)?;
+        assert_eq!(read.rotation_histograms, output.rotation_histograms);
+
+        let without = SPOutput { instance: ext_instance, solution, rotation_histograms: vec![] };
+        assert!(serde_json::to_value(&without)?.get("rotation_histograms").is_none());
+        Ok(())
+    }
+
+    /// Searches in which the focussed samples produced the best sample shift the budget of an item towards focussed samples,
+    /// and large gains of the coordinate descents raise their number. The total number of samples remains the same.
+    #[test]
+    fn adaptive_budget_follows_search_outcomes() {
+        let base = SampleConfig { adaptive_budget: Some(DEFAULT_ADAPTIVE_BUDGET_CONFIG), ..DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config.sample_config };
+        let n_samples = base.n_container_samples + base.n_focussed_samples;
+        let mut budgets = SampleBudgets::new(2, base);
+        let init = budgets.sample_config(0);
+        assert_eq!((init.n_container_samples, init.n_focussed_samples, init.n_coord_descents), (base.n_container_samples, base.n_focussed_samples, base.n_coord_descents));
+
+        for _ in 0..100 {
+            budgets.record(0, &SearchStats { n_evals: 0, best_source: Some(SampleSource::Focussed), cd_gain: 1.0 });
+        }
+        let adapted = budgets.sample_config(0);
+        assert!(adapted.n_focussed_samples > init.n_focussed_samples);
+        assert_eq!(adapted.n_container_samples + adapted.n_focussed_samples, n_samples);
+        //either type of sample keeps its minimum share
+        assert!(adapted.n_container_samples as f64 >= (DEFAULT_ADAPTIVE_BUDGET_CONFIG.min_share * n_samples as f64).floor());
+        assert_eq!(adapted.n_coord_descents, DEFAULT_ADAPTIVE_BUDGET_CONFIG.n_coord_descents_range.1);
+        //other items are not affected
+        assert_eq!(budgets.sample_config(1).n_focussed_samples, init.n_focussed_samples);
+
+        //the next phase continues from the learned budgets
+        let mut next_phase_budgets = SampleBudgets::new(2, base);
+        next_phase_budgets.adopt_stats(&budgets);
+        assert_eq!(next_phase_budgets.sample_config(0).n_focussed_samples, adapted.n_focussed_samples);
+        assert_eq!(next_phase_budgets.sample_config(0).n_coord_descents, adapted.n_coord_descents);
+    }
+
+    /// Without adaptive budgets, the searches of an item do not affect its budget
+    #[test]
+    fn fixed_budget_ignores_search_outcomes() {
+        let base = DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config.sample_config;
+        assert!(base.adaptive_budget.is_none());
+        let mut budgets = SampleBudgets::new(1, base);
+        for _ in 0..100 {
+            budgets.record(0, &SearchStats { n_evals: 0, best_source: Some(SampleSource::Focussed), cd_gain: 1.0 });
+        }
+        let config = budgets.sample_config(0);
+        assert_eq!((config.n_container_samples, config.n_focussed_samples, config.n_coord_descents), (base.n_container_samples, base.n_focussed_samples, base.n_coord_descents));
+        assert!(budgets.format_budget(0).is_none());
+    }
+
+    /// The weights after a single update of a tracker with two overlapping pairs (a large and a small overlap) and a separate item.
+    /// The weight of the small overlap is interpolated between the minimum and maximum increase, if it is selected at all.
+    #[test_case(DEFAULT_WEIGHT_CONFIG, 2.0, true, 1.0; "default")]
+    #[test_case(WeightConfig { pair: WeightSchedule { max_inc_ratio: 3.0, min_inc_ratio: 1.5, decay: 0.5 }, min_weight: 0.1, ..DEFAULT_WEIGHT_CONFIG }, 3.0, true, 0.5; "steeper schedule")]
+    #[test_case(WeightConfig { max_weight: Some(1.6), ..DEFAULT_WEIGHT_CONFIG }, 1.6, true, 1.0; "capped")]
+    #[test_case(WeightConfig { selection: WeightSelection::TopK(1), ..DEFAULT_WEIGHT_CONFIG }, 2.0, false, 1.0; "top 1")]
+    fn weight_update_follows_config(config: WeightConfig, w_large: f64, small_selected: bool, w_none: f64) -> Result<()> {
+        let prob = rectangle_layout(&[(2.0, 1.0, (1.0, 0.53)), (2.0, 1.0, (1.9, 0.61)), (2.0, 1.0, (3.87, 0.58)), (1.0, 1.0, (8.0, 1.0))])?;
+        let pks = prob.layout.placed_items.keys().collect_vec();
+        let mut ct = CollisionTracker::new(&prob.layout);
+        let (large_loss, small_loss) = (ct.get_pair_loss(pks[0], pks[1]), ct.get_pair_loss(pks[1], pks[2]));
+        assert!(large_loss > small_loss && small_loss > 0.0);
+        ct.update_weights(&config);
+
+        let w_small = match small_selected {
+            true => {
+                let schedule = config.pair;
+                let w = schedule.min_inc_ratio + (schedule.max_inc_ratio - schedule.min_inc_ratio) * small_loss / large_loss;
+                config.max_weight.map_or(w, |max| w.min(max))
+            }
+            false => 1.0,
+        };
+        assert_approx_eq!(f64, ct.get_pair_weight(pks[0], pks[1]), w_large, epsilon = 1e-9);
+        assert_approx_eq!(f64, ct.get_pair_weight(pks[1], pks[2]), w_small, epsilon = 1e-9);
+        assert_approx_eq!(f64, ct.get_pair_weight(pks[0], pks[3]), w_none, epsilon = 1e-9);
+        Ok(())
+    }
+
+    /// Writer whose output remains accessible after it is handed to a listener
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A single event stream shared by two jobs writes one parsable JSON line per report, tagged with its job
+    #[test]
+    fn event_stream_writes_a_line_per_report() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let solution = builder.prob.save();
+
+        let buffer = SharedBuffer::default();
+        let mut event_stream = EventStreamListener::new(Box::new(buffer.clone()));
+        let progress = SepProgress {
+            total_loss: 3.0,
+            stats: SepStats { total_moves: 10, total_evals: 100, total_uphill_moves: 2, total_swaps: 1 },
+            elapsed: Duration::from_secs(2),
+            finished: false,
+        };
+        for job in ["qty_1", "qty_2"] {
+            event_stream.start_job(job.to_string());
+            event_stream.report(ReportType::ExplFeas, &solution, &instance);
+            event_stream.report_progress(&progress);
+            event_stream.report(ReportType::ExplInfeas, &solution, &instance);
+            event_stream.report(ReportType::Final, &solution, &instance);
+        }
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone())?;
+        let events = output.lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(events.len(), 6);
+        for (event, (job, report_type)) in events.iter().zip(["qty_1", "qty_2"].into_iter().cartesian_product(["ExplFeas", "ExplInfeas", "Final"])) {
+            assert_eq!(event["job"], job);
+            assert_eq!(event["report_type"], report_type);
+            assert_approx_eq!(f64, event["density"].as_f64().unwrap(), solution.density(&instance));
+            assert_approx_eq!(f64, event["container_width"].as_f64().unwrap(), solution.strip_width());
+        }
+        //the progress of the separator is only reported with the events that follow it, and not carried over to the next job
+        assert!(events[0]["evals_per_sec"].is_null());
+        assert_eq!(events[1]["total_loss"], 3.0);
+        assert_eq!(events[1]["evals_per_sec"], 50.0);
+        assert_eq!(events[1]["swaps"], 1);
+        assert_eq!(events[2]["total_loss"], 0.0);
+        assert!(events[3]["evals_per_sec"].is_null());
+        Ok(())
+    }
+
+    /// The live view renders nothing without viewers and throttles intermediate solutions, but not feasible ones
+    #[test]
+    fn live_view_publishes_only_to_connected_viewers() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let solution = builder.prob.save();
+
+        let server = LiveServer::start(0)?;
+        let mut listener = server.listener("job");
+        listener.report(ReportType::ExplFeas, &solution, &instance);
+
+        let mut stream = TcpStream::connect(server.addr)?;
+        write!(stream, "GET /events HTTP/1.1\r\n\r\n")?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+        let start = Instant::now();
+        while !server.has_clients() {
+            assert!(start.elapsed() < Duration::from_secs(5), "viewer was never registered");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        listener.report(ReportType::ExplImproving, &solution, &instance);
+        listener.report(ReportType::ExplImproving, &solution, &instance);
+        listener.report(ReportType::CmprFeas, &solution, &instance);
+
+        //the report from before the viewer connected is not replayed, the second intermediate one is throttled
+        let mut report_types = vec![];
+        while report_types.len() < 2 {
+            line.clear();
+            reader.read_line(&mut line)?;
+            if let Some(json) = line.strip_prefix("data: ") {
+                let update: serde_json::Value = serde_json::from_str(json)?;
+                assert_eq!(update["job"], "job");
+                report_types.push(update["report_type"].as_str().unwrap().to_string());
+            }
+        }
+        assert_eq!(report_types, ["ExplImproving", "CmprFeas"]);
+        Ok(())
+    }
+
+    /// A recording without a final report, e.g. of a panicked run, is written when the recorder is dropped
+    #[test]
+    fn unfinished_recording_is_written_on_drop() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let solution = builder.prob.save();
+        let path = std::env::temp_dir().join(format!("sparrow_recording_{}.json", std::process::id()));
+
+        let mut recorder = ReplayRecorder::new(&path, "swim");
+        recorder.report(ReportType::ExplFeas, &solution, &instance);
+        recorder.report(ReportType::ExplInfeas, &solution, &instance);
+        drop(recorder);
+
+        let recording = read_recording(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(recording.frames.iter().map(|f| f.report_type.as_str()).collect_vec(), ["ExplFeas", "ExplInfeas"]);
+        assert_eq!(recording.frames[0].placements.len(), solution.layout_snapshot.placed_items.len());
+        assert_eq!(render_frames(&recording, &instance)?.len(), 2);
+        Ok(())
+    }
+
+    /// The instance name is escaped in the title of the HTML player
+    #[test]
+    fn html_player_escapes_title() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let path = std::env::temp_dir().join(format!("sparrow_player_{}.json", std::process::id()));
+        let mut recorder = ReplayRecorder::new(&path, "<script>alert('x')</script> & co");
+        recorder.report(ReportType::ExplFeas, &builder.prob.save(), &instance);
+        let recording = recorder.recording.clone();
+        drop(recorder);
+        std::fs::remove_file(&path)?;
+
+        let html = html_player(&recording, &render_frames(&recording, &instance)?)?;
+        assert!(html.contains("<title>Replay: &lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; co</title>"));
+        assert!(!html.contains("<script>alert"));
+        Ok(())
+    }
+
+    /// The heatmap overlay is only rendered on the intermediate infeasible solutions if enabled
+    #[test_case(None; "disabled")]
+    #[test_case(Some(HeatmapMetric::Loss); "loss")]
+    #[test_case(Some(HeatmapMetric::WeightedLoss); "weighted loss")]
+    fn svg_exporter_overlays_heatmap(metric: Option<HeatmapMetric>) -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let sep_config = DEFAULT_SPARROW_CONFIG.expl_cfg.separator_config;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let mut separator = Separator::new(builder.instance, builder.prob, builder.rng, sep_config);
+        separator.change_size(separator.prob.strip_width() * 0.9, None);
+        assert!(separator.ct.get_total_loss() > 0.0);
+
+        let dir = std::env::temp_dir().join(format!("sparrow_heatmap_{}_{metric:?}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let heatmap = metric.map(|metric| HeatmapOptions { metric, pair_weight_links: true });
+        let mut exporter = SvgExporter::new(None, Some(dir.to_str().unwrap().to_string()), None, heatmap);
+        exporter.report_tracker(&separator.ct);
+        exporter.report(ReportType::ExplInfeas, &separator.prob.save(), &instance);
+        exporter.report(ReportType::ExplFeas, &separator.prob.save(), &instance);
+
+        let svgs = std::fs::read_dir(&dir)?
+            .map(|entry| {
+                let path = entry?.path();
+                Ok((path.file_name().unwrap().to_str().unwrap().to_string(), std::fs::read_to_string(&path)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(svgs.len(), 2);
+        for (file_name, svg) in svgs {
+            let overlaid = metric.is_some() && file_name.ends_with("expl_nf.svg");
+            assert_eq!(svg.contains(r#"id="heatmap_items""#), overlaid, "{file_name}");
+        }
+        Ok(())
+    }
+
+    #[test_case("dump", Some(ControlCommand::Dump); "dump")]
+    #[test_case("  next ", Some(ControlCommand::NextPhase); "next with whitespace")]
+    #[test_case("time 1.5", Some(ControlCommand::SetRemainingTime(Duration::from_millis(1500))); "time")]
+    #[test_case("stop", Some(ControlCommand::Stop); "stop")]
+    #[test_case("time", None; "time without seconds")]
+    #[test_case("time -1", None; "negative time")]
+    #[test_case("time abc", None; "time without a number")]
+    #[test_case("dump now", None; "trailing words")]
+    #[test_case("restart", None; "unknown command")]
+    #[test_case("", None; "empty line")]
+    fn control_command_parsing(line: &str, expected: Option<ControlCommand>) {
+        assert_eq!(ControlCommand::parse(line).ok(), expected);
+    }
+
+    /// Waits for the run controller to pick up the control file
+    fn wait_until(condition: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(5), "control file was not handled");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// The commands of the control file are executed and the file is emptied, invalid lines are skipped.
+    /// Only a dump renders and exports the best solution.
+    #[test]
+    fn control_file_steers_the_run() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let solution = builder.prob.save();
+
+        let dir = std::env::temp_dir().join(format!("sparrow_control_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let control_file = dir.join("control.txt");
+        let mut controller = RunController::new(BasicTerminator::new(), Some(control_file.clone()))?;
+        controller.new_timeout(Duration::from_secs(3600));
+        let mut listener = controller.listener(&dir);
+        listener.report(ReportType::ExplFeas, &solution, &instance);
+        assert!(!dir.join("dump_0.json").exists());
+
+        std::fs::write(&control_file, "dump\nbogus\nnext\n")?;
+        wait_until(|| controller.kill_reason() == Some(KillReason::PhaseSkipped));
+        assert_eq!(std::fs::read_to_string(&control_file)?, "");
+        let dump: ExtSPSolution = serde_json::from_str(&std::fs::read_to_string(dir.join("dump_0.json"))?)?;
+        assert_eq!(dump.layout.placed_items.len(), solution.layout_snapshot.placed_items.len());
+        assert_approx_eq!(f64, dump.strip_width, solution.strip_width());
+        assert!(dir.join("dump_0.svg").exists());
+
+        //a new phase resets the skip, the remaining time overrides the timeout
+        controller.new_timeout(Duration::from_secs(3600));
+        assert_eq!(controller.kill_reason(), None);
+        std::fs::write(&control_file, "time 0\n")?;
+        wait_until(|| controller.kill_reason() == Some(KillReason::Timeout));
+
+        std::fs::write(&control_file, "stop\n")?;
+        wait_until(|| controller.stop_requested());
+        assert_eq!(controller.kill_reason(), Some(KillReason::StopRequested));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    /// Without an explicit copy index, an item is placed as its lowest copy which is not in the layout,
+    /// also after the layout is restored
+    #[test]
+    fn layout_places_lowest_free_copy() -> Result<()> {
+        let instance = import_instance("swim.json")?;
+        let item = instance.item(0);
+        let mut layout = Layout::new(instance.base_strip.into());
+        let copy_idx = |l: &Layout, pk| l.placed_items[pk].copy_idx;
+
+        let pks = (0..3).map(|_| layout.place_item(item, DTransformation::empty())).collect_vec();
+        assert_eq!(pks.iter().map(|&pk| copy_idx(&layout, pk)).collect_vec(), [0, 1, 2]);
+        let snapshot = layout.save();
+
+        layout.remove_item(pks[1]);
+        assert!(!layout.is_copy_placed(0, 1));
+        let pk = layout.place_item(item, DTransformation::empty());
+        assert_eq!(copy_idx(&layout, pk), 1);
+        layout.place_item_copy(item, DTransformation::empty(), 5);
+        assert_eq!(layout.next_copy_idx(0), 3);
+
+        layout.restore(&snapshot);
+        layout.remove_item(pks[0]);
+        assert_eq!(layout.next_copy_idx(0), 0);
+        assert_eq!(layout.next_copy_idx(1), 0);
+        Ok(())
+    }
+
+    /// Every row of the CSV identifies the item and its copy, also for instances with several items
+    #[test]
+    fn csv_round_trip_keeps_item_and_copy_ids() -> Result<()> {
+        let instance = import_instance("shirts.json")?;
+        let builder = LBFBuilder::new(instance.clone(), Xoshiro256PlusPlus::seed_from_u64(0), LBF_SAMPLE_CONFIG).construct();
+        let solution = jagua_rs::probs::spp::io::export(&instance, &builder.prob.save(), Instant::now());
+
+        let path = std::env::temp_dir().join(format!("sparrow_csv_{}.csv", std::process::id()));
+        io::write_csv(&solution, &path, Some(6))?;
+        io::write_csv(&solution, &path, None)?;
+        let solutions = io::read_csv(&path)?;
+        std::fs::remove_file(&path)?;
+
+        let n_items = solution.layout.placed_items.len();
+        assert_eq!(solutions.len(), 2);
+        for (n, placed_items) in solutions {
+            assert_eq!(n, n_items);
+            let ids = placed_items.iter().map(|pi| (pi.item_id, pi.copy_idx)).collect_vec();
+            assert_eq!(ids, solution.layout.placed_items.iter().map(|pi| (pi.item_id, pi.copy_idx)).collect_vec());
+            assert!(ids.iter().any(|&(item_id, _)| item_id > 0));
+            for (read, written) in placed_items.iter().zip(&solution.layout.placed_items) {
+                assert_approx_eq!(f64, read.transformation.translation.0, written.transformation.translation.0, epsilon = 1e-6);
+                assert_approx_eq!(f64, read.transformation.rotation, written.transformation.rotation, epsilon = 1e-6);
+            }
+        }
+        Ok(())
+    }
+
+    /// Ids without an item id, as written by earlier versions and `convert.py`, are read as copies of item 0
+    #[test]
+    fn csv_reads_ids_without_item_id() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("sparrow_csv_legacy_{}.csv", std::process::id()));
+        std::fs::write(&path, "id,x,y,deg\n002_0,s0.5,s0.5,s0\n002_1,s1.5,s0.5,s90\n001_0,s0,s0,s45\n")?;
+        let solutions = io::read_csv(&path)?;
+        std::fs::remove_file(&path)?;
+
+        let ids = solutions.iter()
+            .map(|(n, placed_items)| (*n, placed_items.iter().map(|pi| (pi.item_id, pi.copy_idx)).collect_vec()))
+            .collect_vec();
+        assert_eq!(ids, vec![(2, vec![(0, 0), (0, 1)]), (1, vec![(0, 0)])]);
+        assert_approx_eq!(f64, solutions[0].1[1].transformation.rotation, 90.0);
+        Ok(())
+    }
+}